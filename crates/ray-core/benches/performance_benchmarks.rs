@@ -1,11 +1,13 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use serde_json::{json, Value};
+use std::hint::black_box;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 // Import the application modules we need to benchmark
-use rust_ray_cli::events::EventEntry;
-use rust_ray_cli::event_storage::EventStorage;
+use ray_core::events::EventEntry;
+use ray_core::event_storage::EventStorage;
+use ray_core::server::stream_payloads;
 
 fn create_sample_event(index: usize) -> Value {
     json!({
@@ -113,7 +115,7 @@ fn benchmark_event_processing(c: &mut Criterion) {
                 let events: Vec<Value> = (0..size).map(create_sample_event).collect();
                 b.iter(|| {
                     for event in &events {
-                        let _ = black_box(rust_ray_cli::events::process_event("http", event));
+                        let _ = black_box(ray_core::events::process_event("http", event));
                     }
                 });
             },
@@ -126,7 +128,7 @@ fn benchmark_event_processing(c: &mut Criterion) {
                 let events: Vec<Value> = (0..size).map(create_large_cache_event).collect();
                 b.iter(|| {
                     for event in &events {
-                        let _ = black_box(rust_ray_cli::events::process_event("http", event));
+                        let _ = black_box(ray_core::events::process_event("http", event));
                     }
                 });
             },
@@ -139,7 +141,7 @@ fn benchmark_event_processing(c: &mut Criterion) {
                 let events: Vec<Value> = (0..size).map(create_complex_table_event).collect();
                 b.iter(|| {
                     for event in &events {
-                        let _ = black_box(rust_ray_cli::events::process_event("http", event));
+                        let _ = black_box(ray_core::events::process_event("http", event));
                     }
                 });
             },
@@ -159,16 +161,12 @@ fn benchmark_event_storage(c: &mut Criterion) {
             |b, &size| {
                 b.iter(|| {
                     let storage = Arc::new(EventStorage::new());
-                    let events: Vec<EventEntry> = (0..size)
-                        .map(|i| {
-                            let payload = create_sample_event(i);
-                            rust_ray_cli::events::process_event("http", &payload).unwrap()
-                        })
-                        .collect();
+                    storage.set_console_output(false);
+                    let events: Vec<Value> = (0..size).map(create_sample_event).collect();
                     
                     // Measure insertion performance
                     let start = Instant::now();
-                    for event in events {
+                    for event in &events {
                         storage.add_event(event);
                     }
                     let duration = start.elapsed();
@@ -191,7 +189,7 @@ fn benchmark_json_processing(c: &mut Criterion) {
             size,
             |b, &size| {
                 let large_json = json!({
-                    "data": (0..*size).map(|i| format!("item_{}", i)).collect::<Vec<_>>(),
+                    "data": (0..size).map(|i| format!("item_{}", i)).collect::<Vec<_>>(),
                     "metadata": {
                         "count": size,
                         "generated_at": "2024-07-26T12:00:00Z",
@@ -218,25 +216,73 @@ fn benchmark_json_processing(c: &mut Criterion) {
     group.finish();
 }
 
+fn create_request_body(payloads: Vec<Value>) -> Vec<u8> {
+    serde_json::to_vec(&json!({
+        "uuid": "bench",
+        "payloads": payloads,
+        "meta": {"php_version": "8.3.0", "ray_package_version": "1.41.0"}
+    }))
+    .unwrap()
+}
+
+fn benchmark_payload_ingestion(c: &mut Criterion) {
+    let mut group = c.benchmark_group("payload_ingestion");
+
+    // Parsing a request body the old way (UTF-8 copy, whole Value tree) against streaming
+    // through the payloads array; payloads are only handed to black_box so parsing dominates
+    let mut bodies: Vec<(String, Vec<u8>)> = [100, 1000, 5000]
+        .iter()
+        .map(|&count| {
+            let payloads = (0..count).map(create_sample_event).collect();
+            (format!("{count}_payloads"), create_request_body(payloads))
+        })
+        .collect();
+    // A few large HTML dumps, the case that spiked memory
+    let html = "<div class=\"row\"><span>cell</span></div>".repeat(50_000);
+    let dumps = (0..4)
+        .map(|i| json!({"type": "html", "content": {"html": format!("{i}{html}")}}))
+        .collect();
+    bodies.push(("html_dumps".to_string(), create_request_body(dumps)));
+
+    for (name, body) in &bodies {
+        group.bench_with_input(BenchmarkId::new("full_value", name), body, |b, body| {
+            b.iter(|| {
+                let text = String::from_utf8(body.clone()).unwrap();
+                let request: Value = serde_json::from_str(&text).unwrap();
+                for payload in request["payloads"].as_array().unwrap() {
+                    black_box(payload);
+                }
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("streaming", name), body, |b, body| {
+            b.iter(|| {
+                let count = stream_payloads(body, |payload| {
+                    black_box(payload);
+                })
+                .unwrap();
+                black_box(count);
+            });
+        });
+    }
+
+    group.finish();
+}
+
 fn benchmark_memory_usage(c: &mut Criterion) {
     let mut group = c.benchmark_group("memory_efficiency");
     
     group.bench_function("arc_based_filtering", |b| {
         let storage = Arc::new(EventStorage::new());
+        storage.set_console_output(false);
         
         // Add many events
         for i in 0..5000 {
-            let payload = create_sample_event(i);
-            let event = rust_ray_cli::events::process_event("http", &payload).unwrap();
-            storage.add_event(event);
+            storage.add_event(&create_sample_event(i));
         }
         
         b.iter(|| {
             // Test the Arc-based filtering that avoids cloning
-            let events = storage.get_filtered_events_cached(
-                &std::collections::HashSet::new(),
-                ""
-            );
+            let events = storage.get_events_optimized();
             black_box(events);
         });
     });
@@ -256,7 +302,7 @@ fn benchmark_ui_virtualization(c: &mut Criterion) {
                 let events: Vec<EventEntry> = (0..event_count)
                     .map(|i| {
                         let payload = create_sample_event(i);
-                        rust_ray_cli::events::process_event("http", &payload).unwrap()
+                        ray_core::events::process_event("http", &payload).unwrap()
                     })
                     .collect();
                 
@@ -307,22 +353,18 @@ fn benchmark_fps_simulation(c: &mut Criterion) {
             event_count,
             |b, &event_count| {
                 let storage = Arc::new(EventStorage::new());
+                storage.set_console_output(false);
                 
                 // Pre-populate with events
                 for i in 0..event_count {
-                    let payload = create_sample_event(i);
-                    let event = rust_ray_cli::events::process_event("http", &payload).unwrap();
-                    storage.add_event(event);
+                    storage.add_event(&create_sample_event(i));
                 }
                 
                 b.iter(|| {
                     let frame_start = Instant::now();
                     
                     // Simulate a frame's worth of operations
-                    let events = storage.get_filtered_events_cached(
-                        &std::collections::HashSet::new(),
-                        ""
-                    );
+                    let events = storage.get_events_optimized();
                     
                     // Simulate viewport rendering (20 visible items)
                     let visible_count = std::cmp::min(20, events.len());
@@ -358,6 +400,7 @@ criterion_group!(
     benchmark_event_processing,
     benchmark_event_storage,
     benchmark_json_processing,
+    benchmark_payload_ingestion,
     benchmark_memory_usage,
     benchmark_ui_virtualization,
     benchmark_fps_simulation
@@ -0,0 +1,53 @@
+use crate::editor::EditorConfig;
+use crate::event_storage::StorageConfig;
+use crate::events::cache_timeline::CacheAnalysisConfig;
+use crate::events::frames::FrameConfig;
+use crate::notifications::NotificationConfig;
+use crate::redaction::RedactionConfig;
+use crate::scripts::ScriptConfig;
+use crate::server::ServerConfig;
+use std::sync::OnceLock;
+
+/// Application settings, read once from `RAY_*` environment variables
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    pub editor: EditorConfig,
+    pub frames: FrameConfig,
+    pub cache: CacheAnalysisConfig,
+    pub notifications: NotificationConfig,
+    pub scripts: ScriptConfig,
+    pub redaction: RedactionConfig,
+    pub server: ServerConfig,
+    pub storage: StorageConfig,
+}
+
+impl Config {
+    pub fn from_env() -> Self {
+        Self {
+            editor: EditorConfig::from_env(),
+            frames: FrameConfig::from_env(),
+            cache: CacheAnalysisConfig::from_env(),
+            notifications: NotificationConfig::from_env(),
+            scripts: ScriptConfig::from_env(),
+            redaction: RedactionConfig::from_env(),
+            server: ServerConfig::from_env(),
+            storage: StorageConfig::from_env(),
+        }
+    }
+}
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// Global configuration, loaded lazily on first access
+pub fn config() -> &'static Config {
+    CONFIG.get_or_init(Config::from_env)
+}
+
+/// Parse a boolean setting such as `RAY_HTTP2=off`; unset or unrecognised values give `None`
+pub fn env_flag(name: &str) -> Option<bool> {
+    match std::env::var(name).ok()?.trim().to_lowercase().as_str() {
+        "1" | "true" | "on" | "yes" => Some(true),
+        "0" | "false" | "off" | "no" => Some(false),
+        _ => None,
+    }
+}
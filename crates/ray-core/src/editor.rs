@@ -0,0 +1,166 @@
+use anyhow::{Context, Result};
+use std::process::Command;
+
+/// Default command used when `RAY_EDITOR` is not set
+const DEFAULT_EDITOR_COMMAND: &str = "code -g {file}:{line}";
+
+/// Rewrites a path prefix reported by the client (e.g. inside a container)
+/// to the matching local checkout
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PathMapping {
+    pub from: String,
+    pub to: String,
+}
+
+/// Editor integration settings used by the "open in editor" links
+#[derive(Clone, Debug)]
+pub struct EditorConfig {
+    /// Command template, `{file}` and `{line}` are substituted before launch
+    pub command: String,
+    pub path_mappings: Vec<PathMapping>,
+}
+
+impl Default for EditorConfig {
+    fn default() -> Self {
+        Self {
+            command: DEFAULT_EDITOR_COMMAND.to_string(),
+            path_mappings: Vec::new(),
+        }
+    }
+}
+
+impl EditorConfig {
+    /// Read `RAY_EDITOR` and `RAY_PATH_MAPPINGS` (`/var/www/html=/Users/me/app,...`)
+    pub fn from_env() -> Self {
+        let command = std::env::var("RAY_EDITOR")
+            .ok()
+            .filter(|c| !c.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_EDITOR_COMMAND.to_string());
+
+        let path_mappings = std::env::var("RAY_PATH_MAPPINGS")
+            .map(|raw| parse_path_mappings(&raw))
+            .unwrap_or_default();
+
+        Self {
+            command,
+            path_mappings,
+        }
+    }
+
+    /// Translate a remote path using the first mapping whose prefix matches
+    pub fn map_path(&self, file: &str) -> String {
+        for mapping in &self.path_mappings {
+            if let Some(rest) = file.strip_prefix(&mapping.from) {
+                if rest.is_empty() || rest.starts_with('/') || mapping.from.ends_with('/') {
+                    return format!("{}{}", mapping.to, rest);
+                }
+            }
+        }
+        file.to_string()
+    }
+
+    /// Build the argv for the editor command, substituting placeholders per token
+    /// so mapped paths containing spaces stay a single argument
+    ///
+    /// The path comes from the payload, so one starting with `-` is made relative
+    /// (`./-c`) rather than being read as an editor option.
+    pub fn build_command(&self, file: &str, line: u64) -> Vec<String> {
+        let mut file = self.map_path(file);
+        if file.starts_with('-') {
+            file.insert_str(0, "./");
+        }
+        let line = line.to_string();
+
+        self.command
+            .split_whitespace()
+            .map(|token| token.replace("{file}", &file).replace("{line}", &line))
+            .collect()
+    }
+
+    /// Launch the configured editor without waiting for it to exit
+    pub fn open(&self, file: &str, line: u64) -> Result<()> {
+        let argv = self.build_command(file, line);
+        let (program, args) = argv
+            .split_first()
+            .ok_or_else(|| anyhow::anyhow!("Editor command is empty"))?;
+
+        Command::new(program)
+            .args(args)
+            .spawn()
+            .with_context(|| format!("Failed to launch editor '{program}'"))?;
+
+        Ok(())
+    }
+}
+
+fn parse_path_mappings(raw: &str) -> Vec<PathMapping> {
+    raw.split(',')
+        .filter_map(|pair| {
+            let (from, to) = pair.split_once('=')?;
+            let (from, to) = (from.trim(), to.trim());
+            if from.is_empty() || to.is_empty() {
+                return None;
+            }
+            Some(PathMapping {
+                from: from.to_string(),
+                to: to.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(command: &str, mappings: &str) -> EditorConfig {
+        EditorConfig {
+            command: command.to_string(),
+            path_mappings: parse_path_mappings(mappings),
+        }
+    }
+
+    #[test]
+    fn test_build_command_substitutes_placeholders() {
+        let editor = config("phpstorm --line {line} {file}", "");
+        assert_eq!(
+            editor.build_command("/app/src/Foo.php", 42),
+            vec!["phpstorm", "--line", "42", "/app/src/Foo.php"]
+        );
+    }
+
+    #[test]
+    fn test_build_command_keeps_paths_from_becoming_options() {
+        let editor = config("vim +{line} {file}", "");
+        assert_eq!(
+            editor.build_command("--cmd=!touch /tmp/pwned", 1),
+            vec!["vim", "+1", "./--cmd=!touch /tmp/pwned"]
+        );
+        assert_eq!(
+            config("code -g {file}:{line}", "").build_command("-c", 3),
+            vec!["code", "-g", "./-c:3"]
+        );
+    }
+
+    #[test]
+    fn test_path_mapping_rewrites_prefix() {
+        let editor = config("zed {file}:{line}", "/var/www/html=/Users/me/My Project");
+        assert_eq!(
+            editor.build_command("/var/www/html/app/Models/User.php", 7),
+            vec!["zed", "/Users/me/My Project/app/Models/User.php:7"]
+        );
+    }
+
+    #[test]
+    fn test_path_mapping_requires_segment_boundary() {
+        let editor = config("code -g {file}:{line}", "/var/www/html=/local");
+        assert_eq!(
+            editor.map_path("/var/www/html2/index.php"),
+            "/var/www/html2/index.php"
+        );
+        assert_eq!(
+            editor.map_path("/var/www/html/index.php"),
+            "/local/index.php"
+        );
+    }
+}
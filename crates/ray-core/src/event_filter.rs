@@ -0,0 +1,197 @@
+use crate::events::{EventEntry, EventType};
+use std::collections::HashSet;
+
+/// Criteria for selecting stored events, shared by the GUI list and the HTTP API
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventFilter {
+    /// Allowed event types; `None` allows every type, including unknown ones
+    pub types: Option<HashSet<EventType>>,
+    /// Case-insensitive text matched against label, description and payload
+    pub search: Option<String>,
+    /// Only entries stored at or after this timestamp (`YYYY-MM-DD HH:MM:SS[.fff]`)
+    pub since: Option<String>,
+    /// Only these entry ids, e.g. the occurrences of an exception group
+    pub ids: Option<HashSet<u64>>,
+    /// Only entries tagged with one of these projects; untagged entries never match
+    pub projects: Option<HashSet<String>>,
+}
+
+impl EventFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_types(mut self, types: impl IntoIterator<Item = EventType>) -> Self {
+        self.types = Some(types.into_iter().collect());
+        self
+    }
+
+    pub fn with_search(mut self, search: impl Into<String>) -> Self {
+        let search: String = search.into();
+        self.search = (!search.trim().is_empty()).then(|| search.trim().to_lowercase());
+        self
+    }
+
+    pub fn with_since(mut self, since: impl Into<String>) -> Self {
+        self.since = Some(since.into());
+        self
+    }
+
+    pub fn with_ids(mut self, ids: impl IntoIterator<Item = u64>) -> Self {
+        self.ids = Some(ids.into_iter().collect());
+        self
+    }
+
+    pub fn with_projects(mut self, projects: impl IntoIterator<Item = String>) -> Self {
+        self.projects = Some(projects.into_iter().collect());
+        self
+    }
+
+    /// Parse a filter expression such as `type:log,exception since:2024-01-01T10:00 payment`.
+    ///
+    /// `type:`, `project:` and `since:` tokens set those criteria (a `T` in `since` stands for the
+    /// space in stored timestamps); every other word is part of the search text.
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let mut filter = Self::new();
+        let mut types: Vec<EventType> = Vec::new();
+        let mut projects: Vec<String> = Vec::new();
+        let mut search: Vec<&str> = Vec::new();
+
+        for token in expression.split_whitespace() {
+            if let Some(names) = token.strip_prefix("type:") {
+                for name in names.split(',').filter(|n| !n.is_empty()) {
+                    types.push(name.parse()?);
+                }
+            } else if let Some(names) = token.strip_prefix("project:") {
+                projects.extend(names.split(',').filter(|n| !n.is_empty()).map(str::to_string));
+            } else if let Some(since) = token.strip_prefix("since:") {
+                filter = filter.with_since(since.replacen('T', " ", 1));
+            } else {
+                search.push(token);
+            }
+        }
+
+        if !types.is_empty() {
+            filter = filter.with_types(types);
+        }
+        if !projects.is_empty() {
+            filter = filter.with_projects(projects);
+        }
+        Ok(filter.with_search(search.join(" ")))
+    }
+
+    pub fn matches(&self, entry: &EventEntry) -> bool {
+        if let Some(ids) = &self.ids {
+            if !ids.contains(&entry.id) {
+                return false;
+            }
+        }
+
+        if let Some(types) = &self.types {
+            match entry.event_type.parse::<EventType>() {
+                Ok(event_type) if types.contains(&event_type) => {}
+                _ => return false,
+            }
+        }
+
+        if let Some(projects) = &self.projects {
+            if !entry.project.as_ref().is_some_and(|p| projects.contains(p)) {
+                return false;
+            }
+        }
+
+        // Timestamps are zero-padded, so string order is chronological order
+        if let Some(since) = &self.since {
+            if entry.timestamp.as_str() < since.as_str() {
+                return false;
+            }
+        }
+
+        if let Some(search) = &self.search {
+            let matches = entry.label.to_lowercase().contains(search)
+                || entry.description.to_lowercase().contains(search)
+                || entry
+                    .raw_payload
+                    .to_string()
+                    .to_lowercase()
+                    .contains(search);
+            if !matches {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(id: u64, event_type: &str, timestamp: &str, description: &str) -> EventEntry {
+        EventEntry {
+            id,
+            timestamp: timestamp.to_string(),
+            label: "Log".to_string(),
+            description: description.to_string(),
+            content_type: "custom_ui".to_string(),
+            event_type: event_type.to_string(),
+            project: None,
+            raw_payload: json!({"content": {"values": [description]}}),
+        }
+    }
+
+    #[test]
+    fn test_filter_by_type_search_and_since() {
+        let filter = EventFilter::new()
+            .with_types([EventType::Log])
+            .with_search("Payment")
+            .with_since("2024-01-01 10:00:00");
+
+        assert!(filter.matches(&entry(
+            1,
+            "log",
+            "2024-01-01 10:00:01.000",
+            "payment failed"
+        )));
+        assert!(!filter.matches(&entry(2, "cache", "2024-01-01 10:00:01.000", "payment")));
+        assert!(!filter.matches(&entry(3, "log", "2024-01-01 09:59:59.000", "payment")));
+        assert!(!filter.matches(&entry(4, "log", "2024-01-01 10:00:01.000", "login")));
+    }
+
+    #[test]
+    fn test_parse_expression() {
+        let filter =
+            EventFilter::parse("type:log,request since:2024-01-01T10:00 payment failed").unwrap();
+
+        assert_eq!(
+            filter.types,
+            Some([EventType::Log, EventType::Http].into_iter().collect())
+        );
+        assert_eq!(filter.since.as_deref(), Some("2024-01-01 10:00"));
+        assert_eq!(filter.search.as_deref(), Some("payment failed"));
+        assert_eq!(EventFilter::parse("").unwrap(), EventFilter::new());
+        assert!(EventFilter::parse("type:bogus").is_err());
+    }
+
+    #[test]
+    fn test_empty_filter_keeps_unknown_types() {
+        let unknown = entry(1, "notify", "2024-01-01 10:00:00", "hi");
+        assert!(EventFilter::new().matches(&unknown));
+        assert!(!EventFilter::new()
+            .with_types(EventType::all())
+            .matches(&unknown));
+    }
+
+    #[test]
+    fn test_filter_by_project() {
+        let filter = EventFilter::parse("project:bff,api").unwrap();
+        let mut tagged = entry(1, "log", "2024-01-01 10:00:00", "hi");
+        assert!(!filter.matches(&tagged));
+        tagged.project = Some("api".to_string());
+        assert!(filter.matches(&tagged));
+        tagged.project = Some("worker".to_string());
+        assert!(!filter.matches(&tagged));
+    }
+}
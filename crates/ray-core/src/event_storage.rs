@@ -0,0 +1,673 @@
+use chrono::Local;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::broadcast;
+
+use crate::config::config;
+use crate::event_filter::EventFilter;
+use crate::events::cache_timeline::{cache_key, CacheIndex, CacheKeyHistory};
+use crate::events::exception_groups::{
+    fingerprint, parse_exception, ExceptionGroup, ExceptionGroups,
+};
+use crate::events::{is_known_type, process_event as process_event_directly, registry, EventEntry};
+use crate::metrics::Metrics;
+use crate::notifications::NotificationCenter;
+use crate::scripts::{ScriptOutcome, ScriptRunner, ScriptedPayload};
+
+/// Stored entries buffered for live subscribers before slow ones start missing events
+const STREAM_CAPACITY: usize = 1024;
+/// Diagnostics kept for the app's internal view; older ones are discarded
+const INTERNAL_LOG_CAPACITY: usize = 500;
+
+#[derive(Clone, Debug, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Debug => "DEBUG",
+            Self::Info => "INFO",
+            Self::Warn => "WARN",
+            Self::Error => "ERROR",
+        }
+    }
+}
+
+impl From<LogLevel> for log::Level {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Debug => log::Level::Debug,
+            LogLevel::Info => log::Level::Info,
+            LogLevel::Warn => log::Level::Warn,
+            LogLevel::Error => log::Level::Error,
+        }
+    }
+}
+
+/// A diagnostic from the app itself, as shown in the internal view
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogRecord {
+    pub timestamp: String,
+    pub level: LogLevel,
+    pub source: String,
+    pub message: String,
+}
+
+/// What processing did with a payload
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PayloadStatus {
+    Accepted,
+    /// Stored as an unknown event, since no processor handles the type
+    UnknownType(String),
+    /// A script returned `false`
+    Dropped {
+        script: String,
+    },
+    /// Processing failed; an ingestion error entry with the payload was stored instead
+    Failed(String),
+}
+
+/// How many events are kept in memory
+#[derive(Clone, Debug, Default)]
+pub struct StorageConfig {
+    /// The oldest events are evicted beyond this many; unlimited when `None`
+    pub max_events: Option<usize>,
+}
+
+impl StorageConfig {
+    /// Read `RAY_MAX_EVENTS`; unset or `0` keeps everything
+    pub fn from_env() -> Self {
+        Self {
+            max_events: std::env::var("RAY_MAX_EVENTS")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .filter(|max| *max > 0),
+        }
+    }
+}
+
+/// Summary of what is currently stored
+#[derive(Clone, Debug, Default)]
+pub struct EventStats {
+    pub total: usize,
+    pub by_type: BTreeMap<String, usize>,
+    pub exception_groups: usize,
+    pub generation: u64,
+}
+
+pub struct EventStorage {
+    events: Mutex<VecDeque<Arc<EventEntry>>>,  // Use Arc to avoid cloning large entries
+    server_info: Mutex<String>,
+    console_output: Mutex<bool>,
+    internal_log: Mutex<VecDeque<LogRecord>>,
+    max_events: Mutex<Option<usize>>,
+    /// Approximate size of the stored events, updated under the events lock
+    stored_bytes: AtomicU64,
+    generation: Mutex<u64>,  // Track changes for cache invalidation
+    next_id: Mutex<u64>,
+    exception_groups: Mutex<ExceptionGroups>,
+    cache_index: Mutex<CacheIndex>,
+    notifications: NotificationCenter,
+    scripts: ScriptRunner,
+    stream: broadcast::Sender<Arc<EventEntry>>,
+    metrics: Metrics,
+}
+
+impl EventStorage {
+    pub fn new() -> Self {
+        let (notifications, notification_errors) =
+            NotificationCenter::from_config(&config().notifications);
+
+        let storage = Self {
+            events: Mutex::new(VecDeque::new()),
+            server_info: Mutex::new(String::new()),
+            console_output: Mutex::new(true),
+            internal_log: Mutex::new(VecDeque::new()),
+            max_events: Mutex::new(config().storage.max_events),
+            stored_bytes: AtomicU64::new(0),
+            generation: Mutex::new(0),
+            next_id: Mutex::new(1),
+            exception_groups: Mutex::new(ExceptionGroups::new()),
+            cache_index: Mutex::new(CacheIndex::new()),
+            notifications,
+            scripts: ScriptRunner::new(&config().scripts),
+            stream: broadcast::channel(STREAM_CAPACITY).0,
+            metrics: Metrics::new(),
+        };
+
+        for error in notification_errors {
+            storage.error("Notifications", &error);
+        }
+        for error in registry().load_errors() {
+            storage.error("CustomTypes", error);
+        }
+        for error in &config().redaction.errors {
+            storage.error("Redaction", error);
+        }
+
+        storage
+    }
+
+    // Central logging methods
+    /// Log through the `log` facade (target `ray::<source>`, filtered by `RUST_LOG`)
+    /// and keep anything above debug for the internal view
+    pub fn log(&self, level: LogLevel, source: &str, message: &str) {
+        if level > LogLevel::Debug {
+            let mut internal_log = self.internal_log.lock().unwrap();
+            if internal_log.len() == INTERNAL_LOG_CAPACITY {
+                internal_log.pop_front();
+            }
+            internal_log.push_back(LogRecord {
+                timestamp: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                level,
+                source: source.to_string(),
+                message: message.to_string(),
+            });
+        }
+
+        // Print logs to console unless a full-screen front-end owns the terminal
+        if *self.console_output.lock().unwrap() {
+            log::log!(target: &format!("ray::{source}"), level.into(), "{message}");
+        }
+    }
+
+    // Convenience logging methods
+    pub fn debug(&self, source: &str, message: &str) {
+        self.log(LogLevel::Debug, source, message);
+    }
+
+    pub fn info(&self, source: &str, message: &str) {
+        self.log(LogLevel::Info, source, message);
+    }
+
+    pub fn warn(&self, source: &str, message: &str) {
+        self.log(LogLevel::Warn, source, message);
+    }
+
+    pub fn error(&self, source: &str, message: &str) {
+        self.log(LogLevel::Error, source, message);
+    }
+
+    /// Recent diagnostics, newest first
+    pub fn internal_log(&self) -> Vec<LogRecord> {
+        self.internal_log
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .cloned()
+            .collect()
+    }
+
+    pub fn set_console_output(&self, enabled: bool) {
+        *self.console_output.lock().unwrap() = enabled;
+    }
+
+    /// Evict the oldest events beyond `max_events` from now on; `None` keeps everything
+    pub fn set_max_events(&self, max_events: Option<usize>) {
+        *self.max_events.lock().unwrap() = max_events;
+    }
+
+    pub fn set_server_info(&self, info: String) {
+        let mut server_info = self.server_info.lock().unwrap();
+        *server_info = info;
+    }
+
+    // This method was not needed and could cause unsafe behavior
+
+    /// Run the payload through the user scripts, then process and store it
+    pub fn add_event(&self, event: &Value) {
+        self.add_events(std::slice::from_ref(event));
+    }
+
+    /// Process payloads and store the results together, in order
+    pub fn add_events(&self, events: &[Value]) -> Vec<PayloadStatus> {
+        let (entries, statuses) = self.prepare_entries(events);
+        self.store_entries(entries);
+        statuses
+    }
+
+    /// Scripts, redaction and processing for a batch of payloads, without storing anything
+    ///
+    /// This is the expensive part, so ingestion workers run it in parallel and then
+    /// call [`Self::store_entries`] one batch at a time. Returns a status per payload.
+    pub(crate) fn prepare_entries(
+        &self,
+        events: &[Value],
+    ) -> (Vec<EventEntry>, Vec<PayloadStatus>) {
+        let mut entries = Vec::with_capacity(events.len());
+        let mut statuses = Vec::with_capacity(events.len());
+        for event in events {
+            let (prepared, status) = self.prepare_payload(event, None);
+            entries.extend(prepared);
+            statuses.push(status);
+        }
+        (entries, statuses)
+    }
+
+    /// Entries for a single payload, tagged with the project of the listener it came in on
+    ///
+    /// Usually one entry, plus one for each script error.
+    pub(crate) fn prepare_payload(
+        &self,
+        event: &Value,
+        project: Option<&str>,
+    ) -> (Vec<EventEntry>, PayloadStatus) {
+        let started = Instant::now();
+        let event_type = event
+            .get("type")
+            .and_then(Value::as_str)
+            .filter(|event_type| is_known_type(event_type))
+            .unwrap_or("other");
+        self.metrics.record_payload(event_type);
+
+        let mut entries = Vec::new();
+        let (outcome, errors) = self.scripts.run(event);
+        for error in errors {
+            entries.extend(self.script_error(&error));
+        }
+
+        let status = match outcome {
+            ScriptOutcome::Keep(scripted) => {
+                let (entry, status) = self.prepare_entry(scripted);
+                entries.extend(entry);
+                status
+            }
+            ScriptOutcome::Drop { script } => {
+                self.debug("Scripts", &format!("Event dropped by {script}"));
+                PayloadStatus::Dropped { script }
+            }
+        };
+        for entry in &mut entries {
+            entry.project = project.map(str::to_string);
+        }
+        self.metrics.record_processing(started.elapsed());
+        (entries, status)
+    }
+
+    /// Stand-in for [`Self::prepare_payload`] when processing the payload panicked
+    ///
+    /// The payload is reported as failed and shown as an ingestion error, like any
+    /// other payload that could not be processed.
+    pub(crate) fn payload_panicked(
+        &self,
+        event: &Value,
+        project: Option<&str>,
+        message: &str,
+    ) -> (Vec<EventEntry>, PayloadStatus) {
+        let reason = format!("Processing panicked: {message}");
+        self.error("EventStorage", &reason);
+        self.metrics.record_failure();
+        let mut entries: Vec<EventEntry> = ingestion_error(&reason, event).into_iter().collect();
+        for entry in &mut entries {
+            entry.project = project.map(str::to_string);
+        }
+        (entries, PayloadStatus::Failed(reason))
+    }
+
+    /// Release what an evicted entry still holds: its size, and its place in exception
+    /// groups and cache timelines, so drill-downs only point at stored events
+    fn forget(&self, entry: &EventEntry) {
+        self.stored_bytes
+            .fetch_sub(approximate_size(entry), Ordering::Relaxed);
+        match entry.event_type.as_str() {
+            "exception" => self.exception_groups.lock().unwrap().forget(entry),
+            "cache" => self.cache_index.lock().unwrap().forget(entry),
+            _ => {}
+        }
+    }
+
+    /// Log a script problem and show it in the app as an application log event
+    fn script_error(&self, message: &str) -> Option<EventEntry> {
+        self.error("Scripts", message);
+        // Prepared directly so a broken script can't drop or rewrite its own error
+        self.prepare_entry(ScriptedPayload {
+            payload: json!({
+                "type": "application_log",
+                "content": {"value": message, "level": "error", "channel": "scripts"}
+            }),
+            label: None,
+            description: None,
+        })
+        .0
+    }
+
+    /// Mask sensitive values, then process the payload into an entry
+    fn prepare_entry(&self, mut scripted: ScriptedPayload) -> (Option<EventEntry>, PayloadStatus) {
+        let redaction = &config().redaction;
+        redaction.redact(&mut scripted.payload);
+        let event = &scripted.payload;
+        let event_type = event
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown");
+
+        self.debug(
+            "EventStorage",
+            &format!("Processing event of type: {event_type}"),
+        );
+
+        match process_event_directly(event_type, event) {
+            Ok(mut entry) => {
+                if let Some(label) = &scripted.label {
+                    entry.label = redaction.redact_text(label);
+                }
+                if let Some(description) = &scripted.description {
+                    entry.description = redaction.redact_text(description);
+                }
+                if entry.timestamp.is_empty() {
+                    entry.timestamp = now();
+                }
+
+                self.debug(
+                    "EventStorage",
+                    &format!(
+                        "Event processed successfully: {} ({})",
+                        entry.label, entry.content_type
+                    ),
+                );
+                let status = if is_known_type(event_type) {
+                    PayloadStatus::Accepted
+                } else {
+                    PayloadStatus::UnknownType(event_type.to_string())
+                };
+                (Some(entry), status)
+            }
+            Err(e) => {
+                let reason = format!("Failed to process event of type {event_type}: {e}");
+                self.error("EventStorage", &reason);
+                self.metrics.record_failure();
+                let entry = ingestion_error(&reason, event);
+                (entry, PayloadStatus::Failed(reason))
+            }
+        }
+    }
+
+    /// Assign ids and store prepared entries under a single lock, then send notifications
+    pub(crate) fn store_entries(&self, entries: Vec<EventEntry>) {
+        if entries.is_empty() {
+            return;
+        }
+
+        let mut stored = Vec::with_capacity(entries.len());
+        {
+            let mut events = self.events.lock().unwrap();
+            // Ids are handed out while holding the list, so it stays sorted by id
+            let mut next_id = self.next_id.lock().unwrap();
+            for mut entry in entries {
+                entry.id = *next_id;
+                *next_id += 1;
+
+                match entry.event_type.as_str() {
+                    "exception" => {
+                        self.exception_groups.lock().unwrap().record(&entry);
+                    }
+                    "cache" => {
+                        self.cache_index.lock().unwrap().record(&entry);
+                    }
+                    _ => {}
+                }
+
+                let entry = Arc::new(entry);
+                self.stored_bytes
+                    .fetch_add(approximate_size(&entry), Ordering::Relaxed);
+                events.push_back(Arc::clone(&entry));
+                // Nobody listening is not an error
+                let _ = self.stream.send(Arc::clone(&entry));
+                stored.push(entry);
+            }
+
+            if let Some(max_events) = *self.max_events.lock().unwrap() {
+                let evicted = events.len().saturating_sub(max_events);
+                for _ in 0..evicted {
+                    let Some(entry) = events.pop_front() else {
+                        break;
+                    };
+                    self.forget(&entry);
+                }
+                if evicted > 0 {
+                    self.metrics.record_evictions(evicted);
+                }
+            }
+            self.metrics
+                .set_storage(events.len(), self.stored_bytes.load(Ordering::Relaxed));
+
+            // Increment generation for cache invalidation
+            let mut generation = self.generation.lock().unwrap();
+            *generation += 1;
+        }
+
+        for entry in &stored {
+            for error in self.notifications.dispatch(entry) {
+                self.error("Notifications", &error);
+            }
+        }
+    }
+
+    
+    // Optimized version that returns references to avoid cloning
+    pub fn get_events_optimized(&self) -> Vec<Arc<EventEntry>> {
+        let events = self.events.lock().unwrap();
+        // Reverse iterator without collecting - more memory efficient
+        events.iter().rev().cloned().collect()
+    }
+    
+    
+    
+    
+    pub fn get_event(&self, id: u64) -> Option<Arc<EventEntry>> {
+        let events = self.events.lock().unwrap();
+        // Ids are assigned in insertion order, so the list stays sorted by id
+        events
+            .binary_search_by_key(&id, |entry| entry.id)
+            .ok()
+            .map(|index| events[index].clone())
+    }
+
+    /// Newest-first page of entries matching the filter, starting below the `before` id.
+    /// Also returns the cursor for the next page, if there is one.
+    pub fn query_events(
+        &self,
+        filter: &EventFilter,
+        before: Option<u64>,
+        limit: usize,
+    ) -> (Vec<Arc<EventEntry>>, Option<u64>) {
+        let events = self.events.lock().unwrap();
+        let mut page: Vec<Arc<EventEntry>> = events
+            .iter()
+            .rev()
+            .filter(|entry| before.is_none_or(|before| entry.id < before))
+            .filter(|entry| filter.matches(entry))
+            .take(limit + 1)
+            .cloned()
+            .collect();
+
+        let next_cursor = if page.len() > limit {
+            page.truncate(limit);
+            page.last().map(|entry| entry.id)
+        } else {
+            None
+        };
+
+        (page, next_cursor)
+    }
+
+    pub fn get_stats(&self) -> EventStats {
+        let mut stats = EventStats::default();
+        {
+            let events = self.events.lock().unwrap();
+            stats.total = events.len();
+            for entry in events.iter() {
+                *stats.by_type.entry(entry.event_type.clone()).or_default() += 1;
+            }
+        }
+        stats.exception_groups = self.exception_groups.lock().unwrap().all().len();
+        stats.generation = self.get_generation();
+        stats
+    }
+
+    /// Exception groups, most recently seen first
+    pub fn get_exception_groups(&self) -> Vec<ExceptionGroup> {
+        self.exception_groups.lock().unwrap().all()
+    }
+
+    pub fn get_exception_group(&self, fingerprint: &str) -> Option<ExceptionGroup> {
+        self.exception_groups
+            .lock()
+            .unwrap()
+            .get(fingerprint)
+            .cloned()
+    }
+
+    /// Group the given exception entry belongs to, if it has been stored
+    pub fn get_exception_group_for(&self, entry: &EventEntry) -> Option<ExceptionGroup> {
+        let exception = parse_exception(entry)?;
+        self.get_exception_group(&fingerprint(&exception))
+    }
+
+    /// Full timeline and warnings for the key of the given cache entry
+    pub fn get_cache_history_for(&self, entry: &EventEntry) -> Option<CacheKeyHistory> {
+        let key = cache_key(entry)?;
+        self.get_cache_key_history(&key)
+    }
+
+    pub fn get_cache_key_history(&self, key: &str) -> Option<CacheKeyHistory> {
+        self.cache_index
+            .lock()
+            .unwrap()
+            .history(key, &config().cache)
+    }
+
+    /// Live feed of entries as they are stored
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<EventEntry>> {
+        self.stream.subscribe()
+    }
+
+    pub fn notifications(&self) -> &NotificationCenter {
+        &self.notifications
+    }
+
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    pub fn get_generation(&self) -> u64 {
+        *self.generation.lock().unwrap()
+    }
+
+    pub fn clear_events(&self) {
+        let mut events = self.events.lock().unwrap();
+        events.clear();
+        self.exception_groups.lock().unwrap().clear();
+        self.cache_index.lock().unwrap().clear();
+        self.stored_bytes.store(0, Ordering::Relaxed);
+        self.metrics.set_storage(0, 0);
+        
+        // Increment generation for cache invalidation
+        let mut generation = self.generation.lock().unwrap();
+        *generation += 1;
+    }
+}
+
+/// Entry shown in place of a payload that could not be processed, with the payload attached
+fn ingestion_error(reason: &str, payload: &Value) -> Option<EventEntry> {
+    let error = json!({
+        "type": "application_log",
+        "content": {
+            "value": reason,
+            "level": "error",
+            "channel": "ingestion",
+            "context": {"payload": payload}
+        }
+    });
+    let mut entry = process_event_directly("application_log", &error).ok()?;
+    entry.label = "Ingestion Error".to_string();
+    entry.timestamp = now();
+    Some(entry)
+}
+
+/// Rough memory footprint of an entry: its strings plus the raw payload
+fn approximate_size(entry: &EventEntry) -> u64 {
+    let strings = [
+        &entry.timestamp,
+        &entry.label,
+        &entry.description,
+        &entry.content_type,
+        &entry.event_type,
+    ];
+    let size = std::mem::size_of::<EventEntry>()
+        + strings.iter().map(|s| s.len()).sum::<usize>()
+        + value_size(&entry.raw_payload);
+    size as u64
+}
+
+fn value_size(value: &Value) -> usize {
+    std::mem::size_of::<Value>()
+        + match value {
+            Value::String(s) => s.len(),
+            Value::Array(items) => items.iter().map(value_size).sum(),
+            Value::Object(map) => map.iter().map(|(k, v)| k.len() + value_size(v)).sum(),
+            _ => 0,
+        }
+}
+
+fn now() -> String {
+    Local::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string()
+}
+
+pub fn process_event(event: &Value, storage: &Arc<EventStorage>) {
+    let event_type = event
+        .get("type")
+        .and_then(Value::as_str)
+        .unwrap_or("unknown");
+    storage.debug(
+        "Processing",
+        &format!("Received event of type: {event_type}"),
+    );
+    storage.add_event(event);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eviction_releases_groups_and_timelines() {
+        let storage = EventStorage::new();
+        storage.set_console_output(false);
+        storage.set_max_events(Some(3));
+
+        let exception = json!({"type": "exception", "content": {
+            "class": "RuntimeException",
+            "message": "boom",
+            "frames": [{"file_name": "/app/Job.php", "line_number": 7, "method": "handle"}]
+        }});
+        let cache =
+            json!({"type": "cache", "content": {"values": {"Event": "Hit", "Key": "user:1"}}});
+        storage.add_events(&[exception.clone(), cache, exception.clone(), exception]);
+        for index in 0..2 {
+            storage.add_event(&json!({"type": "log", "content": {"values": [index]}}));
+        }
+
+        // Ids 1 to 3 are gone; only the exception still stored is left to drill into
+        let ids: Vec<u64> = storage
+            .get_events_optimized()
+            .iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(ids, [6, 5, 4]);
+        let group = &storage.get_exception_groups()[0];
+        assert_eq!((group.count, group.occurrence_ids.clone()), (3, vec![4]));
+        assert!(storage.get_cache_key_history("user:1").is_none());
+        assert_eq!(storage.get_event(4).unwrap().event_type, "exception");
+        assert!(storage.get_event(1).is_none());
+    }
+}
@@ -0,0 +1,43 @@
+use crate::events::base::{extract_timestamp, truncate, EventEntry};
+use crate::events::processors::process_application_log_event;
+use crate::events::types::ProcessedEvent;
+use anyhow::Result;
+use serde_json::Value;
+
+pub fn process(payload: &Value) -> Result<EventEntry> {
+    let mut entry = EventEntry {
+        id: 0,
+        timestamp: extract_timestamp(payload),
+        label: "Application Log".to_string(),
+        description: String::new(),
+        content_type: "custom_ui".to_string(),
+        event_type: "application_log".to_string(),
+        project: None,
+        raw_payload: payload.clone(),
+    };
+
+    if let Some(content) = payload.get("content") {
+        // Process using the new architecture
+        let processed_event = process_application_log_event(content)?;
+
+        // Set labels and descriptions based on processed event
+        if let ProcessedEvent::ApplicationLog(ref app_log_event) = processed_event {
+            entry.label = "Application Log".to_string();
+            entry.description = if !app_log_event.message.is_empty() {
+                if app_log_event.message.len() > 50 {
+                    format!("{}...", truncate(&app_log_event.message, 50).trim())
+                } else {
+                    app_log_event.message.clone()
+                }
+            } else {
+                "Empty log".to_string()
+            };
+        } else {
+            return Err(anyhow::anyhow!(
+                "Unexpected event type from application log processor"
+            ));
+        }
+    }
+
+    Ok(entry)
+}
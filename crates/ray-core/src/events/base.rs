@@ -0,0 +1,100 @@
+use anyhow::Result;
+use serde_json::Value;
+use std::fmt;
+use std::sync::Arc;
+
+/// Represents a processed event entry
+#[derive(Clone, Debug)]
+pub struct EventEntry {
+    /// Sequential id assigned by `EventStorage` when the entry is stored (0 before that)
+    pub id: u64,
+    pub timestamp: String,
+    pub label: String,
+    pub description: String,
+    pub content_type: String,
+    pub event_type: String,
+    /// Project of the listener the payload arrived on, when listeners are tagged
+    pub project: Option<String>,
+    pub raw_payload: Value,
+}
+
+impl EventEntry {
+    /// JSON representation used by the HTTP API
+    pub fn to_json(&self) -> Value {
+        serde_json::json!({
+            "id": self.id,
+            "timestamp": self.timestamp,
+            "label": self.label,
+            "description": self.description,
+            "content_type": self.content_type,
+            "event_type": self.event_type,
+            "project": self.project,
+            "payload": self.raw_payload,
+        })
+    }
+}
+
+/// Turns the raw payload of one event type into an `EventEntry`
+///
+/// Processors are looked up by payload type name in the `EventRegistry`, so
+/// custom types can be added without touching the built-in ones.
+#[derive(Clone)]
+pub struct EventProcessor(Arc<ProcessFn>);
+
+type ProcessFn = dyn Fn(&Value) -> Result<EventEntry> + Send + Sync;
+
+impl EventProcessor {
+    pub fn new(process: impl Fn(&Value) -> Result<EventEntry> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(process))
+    }
+
+    /// Process a raw event payload into an EventEntry
+    pub fn process(&self, payload: &Value) -> Result<EventEntry> {
+        (self.0)(payload)
+    }
+}
+
+impl fmt::Debug for EventProcessor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EventProcessor")
+    }
+}
+
+/// Helper function to extract timestamp from event payload
+pub fn extract_timestamp(payload: &Value) -> String {
+    payload
+        .get("timestamp")
+        .and_then(|t| t.as_str())
+        .unwrap_or("")
+        .to_string()
+}
+
+/// The first `max` bytes of `text`, cut back to a character boundary
+pub fn truncate(text: &str, max: usize) -> &str {
+    let mut end = max.min(text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+/// Resolve a dotted path against the payload root, then `content` and
+/// `content.values`, so `Status` works as well as `content.values.Status`
+pub fn resolve_field<'a>(payload: &'a Value, path: &str) -> Option<&'a Value> {
+    let lookup = |root: &'a Value| {
+        path.split('.')
+            .try_fold(root, |value, segment| match value {
+                Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+                _ => value.get(segment),
+            })
+    };
+
+    lookup(payload)
+        .or_else(|| payload.get("content").and_then(lookup))
+        .or_else(|| {
+            payload
+                .get("content")
+                .and_then(|content| content.get("values"))
+                .and_then(lookup)
+        })
+}
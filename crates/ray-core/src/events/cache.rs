@@ -0,0 +1,41 @@
+use crate::events::base::{extract_timestamp, EventEntry};
+use crate::events::processors::cache::process_cache_event;
+use crate::events::types::ProcessedEvent;
+use anyhow::Result;
+use serde_json::Value;
+
+pub fn process(payload: &Value) -> Result<EventEntry> {
+    let mut entry = EventEntry {
+        id: 0,
+        timestamp: extract_timestamp(payload),
+        label: "cache".to_string(),
+        description: String::new(),
+        content_type: "custom_ui".to_string(),
+        event_type: "cache".to_string(),
+        project: None,
+        raw_payload: payload.clone(),
+    };
+
+    if let Some(content) = payload.get("content") {
+        // Process using the new architecture
+        let processed_event = process_cache_event(content)?;
+
+        // Set labels and descriptions based on processed event
+        if let ProcessedEvent::Cache(ref cache_event) = processed_event {
+            entry.label = format!("Cache: {}", cache_event.operation);
+            entry.description = match cache_event.operation.as_str() {
+                "Hit" => format!("Cache hit for: {}", cache_event.key),
+                "Missed" => format!("Cache miss for: {}", cache_event.key),
+                "Key written" => format!("Cache write: {}", cache_event.key),
+                "Forgotten" => format!("Cache key forgotten: {}", cache_event.key),
+                _ => format!("{} ({})", cache_event.operation, cache_event.key),
+            };
+        } else {
+            return Err(anyhow::anyhow!(
+                "Unexpected event type from cache processor"
+            ));
+        }
+    }
+
+    Ok(entry)
+}
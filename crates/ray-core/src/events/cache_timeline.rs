@@ -0,0 +1,318 @@
+use crate::events::base::EventEntry;
+use crate::events::processors::cache::process_cache_event;
+use crate::events::types::ProcessedEvent;
+use chrono::NaiveDateTime;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Settings for the suspicious-pattern checks on cache keys
+#[derive(Clone, Debug)]
+pub struct CacheAnalysisConfig {
+    /// Events closer than this are considered part of the same request
+    pub request_window_ms: i64,
+    /// TTLs below this many seconds are flagged
+    pub short_ttl_seconds: u64,
+}
+
+impl Default for CacheAnalysisConfig {
+    fn default() -> Self {
+        Self {
+            request_window_ms: 1000,
+            short_ttl_seconds: 5,
+        }
+    }
+}
+
+impl CacheAnalysisConfig {
+    /// Read `RAY_CACHE_REQUEST_WINDOW_MS` and `RAY_CACHE_SHORT_TTL`
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            request_window_ms: std::env::var("RAY_CACHE_REQUEST_WINDOW_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.request_window_ms),
+            short_ttl_seconds: std::env::var("RAY_CACHE_SHORT_TTL")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.short_ttl_seconds),
+        }
+    }
+}
+
+/// One operation on a cache key
+#[derive(Clone, Debug)]
+pub struct CacheTimelineEntry {
+    pub id: u64,
+    pub timestamp: String,
+    pub operation: String,
+    pub value: Option<Value>,
+    pub expiration_seconds: Option<u64>,
+    pub ttl: Option<String>,
+    pub store: Option<String>,
+}
+
+impl CacheTimelineEntry {
+    fn is_write(&self) -> bool {
+        self.operation == "Key written"
+    }
+
+    fn is_miss(&self) -> bool {
+        self.operation == "Missed"
+    }
+
+    /// TTL in seconds, from the expiration field or a TTL like "30s"/"30"
+    fn ttl_seconds(&self) -> Option<u64> {
+        self.expiration_seconds.or_else(|| {
+            self.ttl
+                .as_deref()
+                .map(|ttl| ttl.trim().trim_end_matches('s'))
+                .and_then(|ttl| ttl.parse().ok())
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CacheWarning {
+    /// A miss directly followed a write - the write may use a different store or key
+    MissAfterWrite {
+        write_id: u64,
+        miss_id: u64,
+    },
+    /// The key was written several times within one request window
+    RepeatedWrites {
+        ids: Vec<u64>,
+    },
+    ShortTtl {
+        id: u64,
+        seconds: u64,
+    },
+}
+
+impl CacheWarning {
+    pub fn description(&self) -> String {
+        match self {
+            CacheWarning::MissAfterWrite { .. } => "Miss right after a write".to_string(),
+            CacheWarning::RepeatedWrites { ids } => {
+                format!("Written {} times within one request", ids.len())
+            }
+            CacheWarning::ShortTtl { seconds, .. } => format!("Very short TTL ({seconds}s)"),
+        }
+    }
+
+    /// Timeline entries the warning refers to
+    pub fn event_ids(&self) -> Vec<u64> {
+        match self {
+            CacheWarning::MissAfterWrite { write_id, miss_id } => vec![*write_id, *miss_id],
+            CacheWarning::RepeatedWrites { ids } => ids.clone(),
+            CacheWarning::ShortTtl { id, .. } => vec![*id],
+        }
+    }
+}
+
+/// Everything recorded for a single key, oldest first
+#[derive(Clone, Debug)]
+pub struct CacheKeyHistory {
+    pub key: String,
+    pub entries: Vec<CacheTimelineEntry>,
+    pub warnings: Vec<CacheWarning>,
+}
+
+/// Cache events indexed by key
+#[derive(Default)]
+pub struct CacheIndex {
+    keys: HashMap<String, Vec<CacheTimelineEntry>>,
+}
+
+impl CacheIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a stored cache entry, returning its key
+    pub fn record(&mut self, entry: &EventEntry) -> Option<String> {
+        let content = entry.raw_payload.get("content")?;
+        let Ok(ProcessedEvent::Cache(cache_event)) = process_cache_event(content) else {
+            return None;
+        };
+
+        self.keys
+            .entry(cache_event.key.clone())
+            .or_default()
+            .push(CacheTimelineEntry {
+                id: entry.id,
+                timestamp: entry.timestamp.clone(),
+                operation: cache_event.operation,
+                value: cache_event.value,
+                expiration_seconds: cache_event.expiration_seconds,
+                ttl: cache_event.ttl,
+                store: cache_event.store,
+            });
+
+        Some(cache_event.key)
+    }
+
+    /// Drop an evicted entry from its key's timeline, and the key once it is empty
+    pub fn forget(&mut self, entry: &EventEntry) {
+        let Some(key) = cache_key(entry) else {
+            return;
+        };
+        if let Some(timeline) = self.keys.get_mut(&key) {
+            timeline.retain(|operation| operation.id != entry.id);
+            if timeline.is_empty() {
+                self.keys.remove(&key);
+            }
+        }
+    }
+
+    pub fn history(&self, key: &str, config: &CacheAnalysisConfig) -> Option<CacheKeyHistory> {
+        let entries = self.keys.get(key)?.clone();
+        let warnings = analyze(&entries, config);
+        Some(CacheKeyHistory {
+            key: key.to_string(),
+            entries,
+            warnings,
+        })
+    }
+
+    pub fn clear(&mut self) {
+        self.keys.clear();
+    }
+}
+
+/// Cache key of a stored cache entry
+pub fn cache_key(entry: &EventEntry) -> Option<String> {
+    if entry.event_type != "cache" {
+        return None;
+    }
+    let content = entry.raw_payload.get("content")?;
+    match process_cache_event(content) {
+        Ok(ProcessedEvent::Cache(cache_event)) => Some(cache_event.key),
+        _ => None,
+    }
+}
+
+/// Flag suspicious patterns in a key's timeline
+pub fn analyze(entries: &[CacheTimelineEntry], config: &CacheAnalysisConfig) -> Vec<CacheWarning> {
+    let mut warnings = Vec::new();
+
+    for pair in entries.windows(2) {
+        let (previous, next) = (&pair[0], &pair[1]);
+        if previous.is_write()
+            && next.is_miss()
+            // Adjacent events still count when timestamps can't be compared
+            && within_window(previous, next, config.request_window_ms).unwrap_or(true)
+        {
+            warnings.push(CacheWarning::MissAfterWrite {
+                write_id: previous.id,
+                miss_id: next.id,
+            });
+        }
+    }
+
+    let writes: Vec<&CacheTimelineEntry> = entries.iter().filter(|e| e.is_write()).collect();
+    let mut cluster: Vec<u64> = Vec::new();
+    for (index, write) in writes.iter().enumerate() {
+        let continues = index > 0
+            && within_window(writes[index - 1], write, config.request_window_ms).unwrap_or(false);
+        if !continues {
+            if cluster.len() > 1 {
+                warnings.push(CacheWarning::RepeatedWrites {
+                    ids: std::mem::take(&mut cluster),
+                });
+            }
+            cluster.clear();
+        }
+        cluster.push(write.id);
+    }
+    if cluster.len() > 1 {
+        warnings.push(CacheWarning::RepeatedWrites { ids: cluster });
+    }
+
+    for entry in entries.iter().filter(|e| e.is_write()) {
+        if let Some(seconds) = entry.ttl_seconds() {
+            if seconds < config.short_ttl_seconds {
+                warnings.push(CacheWarning::ShortTtl {
+                    id: entry.id,
+                    seconds,
+                });
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Whether two events fall within the request window, `None` if either
+/// timestamp can't be parsed
+fn within_window(a: &CacheTimelineEntry, b: &CacheTimelineEntry, window_ms: i64) -> Option<bool> {
+    let (a, b) = (
+        parse_timestamp(&a.timestamp)?,
+        parse_timestamp(&b.timestamp)?,
+    );
+    Some((b - a).num_milliseconds().abs() <= window_ms)
+}
+
+fn parse_timestamp(timestamp: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S%.f").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timeline_entry(id: u64, timestamp: &str, operation: &str) -> CacheTimelineEntry {
+        CacheTimelineEntry {
+            id,
+            timestamp: timestamp.to_string(),
+            operation: operation.to_string(),
+            value: None,
+            expiration_seconds: None,
+            ttl: None,
+            store: None,
+        }
+    }
+
+    #[test]
+    fn test_flags_miss_after_write() {
+        let entries = vec![
+            timeline_entry(1, "2024-01-01 10:00:00.000", "Key written"),
+            timeline_entry(2, "2024-01-01 10:00:00.200", "Missed"),
+            timeline_entry(3, "2024-01-01 10:05:00.000", "Hit"),
+        ];
+
+        let warnings = analyze(&entries, &CacheAnalysisConfig::default());
+        assert_eq!(
+            warnings,
+            vec![CacheWarning::MissAfterWrite {
+                write_id: 1,
+                miss_id: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn test_flags_repeated_writes_within_window() {
+        let entries = vec![
+            timeline_entry(1, "2024-01-01 10:00:00.000", "Key written"),
+            timeline_entry(2, "2024-01-01 10:00:00.100", "Key written"),
+            timeline_entry(3, "2024-01-01 10:00:00.300", "Key written"),
+            timeline_entry(4, "2024-01-01 11:00:00.000", "Key written"),
+        ];
+
+        let warnings = analyze(&entries, &CacheAnalysisConfig::default());
+        assert_eq!(
+            warnings,
+            vec![CacheWarning::RepeatedWrites { ids: vec![1, 2, 3] }]
+        );
+    }
+
+    #[test]
+    fn test_flags_short_ttl() {
+        let mut write = timeline_entry(1, "2024-01-01 10:00:00.000", "Key written");
+        write.ttl = Some("2s".to_string());
+
+        let warnings = analyze(&[write], &CacheAnalysisConfig::default());
+        assert_eq!(warnings, vec![CacheWarning::ShortTtl { id: 1, seconds: 2 }]);
+    }
+}
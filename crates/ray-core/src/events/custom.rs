@@ -0,0 +1,210 @@
+use crate::events::base::{extract_timestamp, resolve_field, EventEntry};
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::Value;
+
+/// One labelled value shown in the details of a custom event
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomField {
+    pub label: String,
+    /// Dotted path, resolved like alert rule fields (root, `content`, `content.values`)
+    pub path: String,
+}
+
+/// A payload type described by field mappings in the `RAY_CUSTOM_TYPES` file
+#[derive(Clone, Debug, PartialEq)]
+pub struct CustomTypeDefinition {
+    pub name: String,
+    pub display_name: String,
+    pub aliases: Vec<String>,
+    /// Templates with `{path}` placeholders for the list label and description
+    pub label: String,
+    pub description: String,
+    pub fields: Vec<CustomField>,
+}
+
+impl CustomTypeDefinition {
+    pub fn from_json(value: &Value) -> Result<Self> {
+        let text = |key: &str| value.get(key).and_then(Value::as_str).map(str::to_string);
+
+        let name = text("name")
+            .filter(|name| !name.trim().is_empty())
+            .ok_or_else(|| anyhow!("custom type without a name"))?;
+        let display_name = text("display_name").unwrap_or_else(|| name.clone());
+
+        let aliases = match value.get("aliases") {
+            None => Vec::new(),
+            Some(Value::Array(items)) => items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect(),
+            Some(_) => bail!("{name}: aliases must be an array of strings"),
+        };
+
+        let fields = match value.get("fields") {
+            None => Vec::new(),
+            Some(Value::Array(items)) => items
+                .iter()
+                .map(|item| {
+                    let path = item
+                        .get("path")
+                        .and_then(Value::as_str)
+                        .ok_or_else(|| anyhow!("{name}: every field needs a path"))?;
+                    let label = item.get("label").and_then(Value::as_str).unwrap_or(path);
+                    Ok(CustomField {
+                        label: label.to_string(),
+                        path: path.to_string(),
+                    })
+                })
+                .collect::<Result<_>>()?,
+            Some(_) => bail!("{name}: fields must be an array"),
+        };
+
+        Ok(Self {
+            label: text("label").unwrap_or_else(|| display_name.clone()),
+            description: text("description").unwrap_or_default(),
+            name,
+            display_name,
+            aliases,
+            fields,
+        })
+    }
+
+    pub fn process(&self, payload: &Value) -> Result<EventEntry> {
+        Ok(EventEntry {
+            id: 0,
+            timestamp: extract_timestamp(payload),
+            label: render_template(&self.label, payload),
+            description: render_template(&self.description, payload),
+            content_type: "custom_ui".to_string(),
+            event_type: self.name.clone(),
+            project: None,
+            raw_payload: payload.clone(),
+        })
+    }
+
+    /// Labelled values for the details view, skipping fields the payload doesn't have
+    pub fn field_values(&self, payload: &Value) -> Vec<(String, String)> {
+        self.fields
+            .iter()
+            .filter_map(|field| {
+                let value = resolve_field(payload, &field.path)?;
+                Some((field.label.clone(), value_text(value)))
+            })
+            .collect()
+    }
+}
+
+/// Replace each `{path}` with the payload value it resolves to, or nothing
+fn render_template(template: &str, payload: &Value) -> String {
+    let mut output = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let Some(length) = rest[start..].find('}') else {
+            break;
+        };
+        output.push_str(&rest[..start]);
+        let path = rest[start + 1..start + length].trim();
+        if let Some(value) = resolve_field(payload, path) {
+            output.push_str(&value_text(value));
+        }
+        rest = &rest[start + length + 1..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Declarative types loaded from the JSON file named by `RAY_CUSTOM_TYPES`
+#[derive(Clone, Debug, Default)]
+pub struct CustomTypesConfig {
+    pub definitions: Vec<CustomTypeDefinition>,
+    /// Problems with the file or individual definitions, logged at startup
+    pub errors: Vec<String>,
+}
+
+impl CustomTypesConfig {
+    pub fn from_env() -> Self {
+        match std::env::var("RAY_CUSTOM_TYPES") {
+            Ok(path) if !path.is_empty() => match std::fs::read_to_string(&path)
+                .with_context(|| format!("Could not read {path}"))
+                .and_then(|raw| Self::parse(&raw))
+            {
+                Ok(config) => config,
+                Err(e) => Self {
+                    definitions: Vec::new(),
+                    errors: vec![format!("{e:#}")],
+                },
+            },
+            _ => Self::default(),
+        }
+    }
+
+    /// `{"types": [...]}`; a broken definition is reported without dropping the others
+    pub fn parse(raw: &str) -> Result<Self> {
+        let document: Value = serde_json::from_str(raw).context("Invalid custom types JSON")?;
+        let types = document
+            .get("types")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow!("Custom types file needs a \"types\" array"))?;
+
+        let mut config = Self::default();
+        for value in types {
+            match CustomTypeDefinition::from_json(value) {
+                Ok(definition) => config.definitions.push(definition),
+                Err(e) => config.errors.push(e.to_string()),
+            }
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_and_process() {
+        let config = CustomTypesConfig::parse(
+            r#"{"types": [
+                {
+                    "name": "feature_flag",
+                    "display_name": "Feature Flag",
+                    "label": "Flag: {flag}",
+                    "description": "{flag} is {enabled} for user {user.id}",
+                    "fields": [{"label": "Flag", "path": "flag"}, {"path": "user.id"}, {"path": "missing"}]
+                },
+                {"display_name": "No name"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(config.definitions.len(), 1);
+        assert_eq!(config.errors, vec!["custom type without a name"]);
+
+        let definition = &config.definitions[0];
+        let payload = json!({
+            "type": "feature_flag",
+            "content": {"flag": "new-checkout", "enabled": true, "user": {"id": 42}}
+        });
+        let entry = definition.process(&payload).unwrap();
+        assert_eq!(entry.event_type, "feature_flag");
+        assert_eq!(entry.label, "Flag: new-checkout");
+        assert_eq!(entry.description, "new-checkout is true for user 42");
+        assert_eq!(
+            definition.field_values(&payload),
+            vec![
+                ("Flag".to_string(), "new-checkout".to_string()),
+                ("user.id".to_string(), "42".to_string()),
+            ]
+        );
+    }
+}
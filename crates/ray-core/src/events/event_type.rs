@@ -1,3 +1,4 @@
+use crate::events::registry::registry;
 use std::fmt;
 use std::str::FromStr;
 
@@ -11,12 +12,14 @@ pub enum EventType {
     Exception,
     ApplicationLog,
     // Table removed - was an anti-pattern dispatcher, not a real event type
+    /// Type registered at runtime, by name
+    Custom(&'static str),
 }
 
 impl EventType {
-    /// Get all available event types as a vec
+    /// Get all available event types as a vec, built-in ones first
     pub fn all() -> Vec<EventType> {
-        vec![
+        let mut all = vec![
             EventType::Cache,
             EventType::Http,
             EventType::Log,
@@ -24,7 +27,35 @@ impl EventType {
             EventType::Exception,
             EventType::ApplicationLog,
             // Table removed - was an anti-pattern dispatcher
-        ]
+        ];
+        all.extend(registry().custom_types());
+        all
+    }
+
+    /// Built-in type for a payload type name or its legacy alias
+    pub fn builtin(name: &str) -> Option<EventType> {
+        match name {
+            "cache" => Some(EventType::Cache),
+            "http" | "request" => Some(EventType::Http), // Support both http and request
+            "log" => Some(EventType::Log),
+            "query" | "executed_query" => Some(EventType::Query), // Support both query variants
+            "exception" => Some(EventType::Exception),
+            "application_log" => Some(EventType::ApplicationLog),
+            // "table" removed - was an anti-pattern dispatcher, not a real event type
+            _ => None,
+        }
+    }
+
+    /// Every payload type name that maps to this type, including the canonical one
+    pub fn names(&self) -> Vec<String> {
+        let mut names = vec![self.as_str().to_string()];
+        match self {
+            EventType::Http => names.push("request".to_string()),
+            EventType::Query => names.push("executed_query".to_string()),
+            EventType::Custom(name) => names.extend(registry().aliases(name)),
+            _ => {}
+        }
+        names
     }
 
     /// Convert to string representation used in the UI
@@ -37,6 +68,7 @@ impl EventType {
             EventType::Exception => "exception",
             EventType::ApplicationLog => "application_log",
             // Table removed - was an anti-pattern dispatcher
+            EventType::Custom(name) => name,
         }
     }
 
@@ -50,6 +82,7 @@ impl EventType {
             EventType::Exception => "Exception",
             EventType::ApplicationLog => "Application Log",
             // Table removed - was an anti-pattern dispatcher
+            EventType::Custom(name) => registry().display_name(name).unwrap_or(*name),
         }
     }
 }
@@ -64,16 +97,9 @@ impl FromStr for EventType {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "cache" => Ok(EventType::Cache),
-            "http" | "request" => Ok(EventType::Http), // Support both http and request
-            "log" => Ok(EventType::Log),
-            "query" | "executed_query" => Ok(EventType::Query), // Support both query variants
-            "exception" => Ok(EventType::Exception),
-            "application_log" => Ok(EventType::ApplicationLog),
-            // "table" removed - was an anti-pattern dispatcher, not a real event type
-            _ => Err(format!("Unknown event type: {}", s)),
-        }
+        EventType::builtin(s)
+            .or_else(|| registry().event_type(s))
+            .ok_or_else(|| format!("Unknown event type: {}", s))
     }
 }
 
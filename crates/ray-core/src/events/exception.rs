@@ -0,0 +1,46 @@
+use crate::events::base::{extract_timestamp, truncate, EventEntry};
+use crate::events::processors::process_exception_event;
+use crate::events::types::ProcessedEvent;
+use anyhow::Result;
+use serde_json::Value;
+
+pub fn process(payload: &Value) -> Result<EventEntry> {
+    let mut entry = EventEntry {
+        id: 0,
+        timestamp: extract_timestamp(payload),
+        label: "Exception".to_string(),
+        description: String::new(),
+        content_type: "custom_ui".to_string(),
+        event_type: "exception".to_string(),
+        project: None,
+        raw_payload: payload.clone(),
+    };
+
+    if let Some(content) = payload.get("content") {
+        // Process using the new architecture
+        let processed_event = process_exception_event(content)?;
+
+        // Set labels and descriptions based on processed event
+        if let ProcessedEvent::Exception(ref exception_event) = processed_event {
+            entry.label = "Exception".to_string();
+            let description = if !exception_event.message.is_empty() {
+                format!("{}: {}", exception_event.class, exception_event.message)
+            } else {
+                exception_event.class.clone()
+            };
+
+            // Truncate long descriptions
+            if description.len() > 100 {
+                entry.description = format!("{}...", truncate(&description, 97));
+            } else {
+                entry.description = description;
+            }
+        } else {
+            return Err(anyhow::anyhow!(
+                "Unexpected event type from exception processor"
+            ));
+        }
+    }
+
+    Ok(entry)
+}
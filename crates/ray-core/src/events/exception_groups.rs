@@ -0,0 +1,320 @@
+use crate::config::config;
+use crate::events::base::EventEntry;
+use crate::events::frames::FrameKind;
+use crate::events::processors::process_exception_event;
+use crate::events::types::{ExceptionEvent, ProcessedEvent, StackFrame};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Number of application frames that contribute to a fingerprint
+const FINGERPRINT_FRAMES: usize = 3;
+
+/// Aggregated view over every occurrence of the "same" exception
+#[derive(Clone, Debug)]
+pub struct ExceptionGroup {
+    pub fingerprint: String,
+    pub class: String,
+    /// Message of the most recent occurrence
+    pub message: String,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub count: usize,
+    /// Ids of the stored entries, oldest first
+    pub occurrence_ids: Vec<u64>,
+}
+
+/// Exception groups keyed by fingerprint
+#[derive(Default)]
+pub struct ExceptionGroups {
+    groups: HashMap<String, ExceptionGroup>,
+}
+
+impl ExceptionGroups {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a stored exception entry, returning the fingerprint it was grouped under
+    pub fn record(&mut self, entry: &EventEntry) -> Option<String> {
+        let exception = parse_exception(entry)?;
+        let fingerprint = fingerprint(&exception);
+
+        let group = self
+            .groups
+            .entry(fingerprint.clone())
+            .or_insert_with(|| ExceptionGroup {
+                fingerprint: fingerprint.clone(),
+                class: exception.class.clone(),
+                message: String::new(),
+                first_seen: entry.timestamp.clone(),
+                last_seen: String::new(),
+                count: 0,
+                occurrence_ids: Vec::new(),
+            });
+
+        group.message = exception.message;
+        group.last_seen = entry.timestamp.clone();
+        group.count += 1;
+        group.occurrence_ids.push(entry.id);
+
+        Some(fingerprint)
+    }
+
+    /// Drop an evicted entry from its group's occurrences, and the group once none are left
+    ///
+    /// The count keeps including evicted occurrences while the group is around.
+    pub fn forget(&mut self, entry: &EventEntry) {
+        let Some(exception) = parse_exception(entry) else {
+            return;
+        };
+        let fingerprint = fingerprint(&exception);
+        if let Some(group) = self.groups.get_mut(&fingerprint) {
+            group.occurrence_ids.retain(|id| *id != entry.id);
+            if group.occurrence_ids.is_empty() {
+                self.groups.remove(&fingerprint);
+            }
+        }
+    }
+
+    pub fn get(&self, fingerprint: &str) -> Option<&ExceptionGroup> {
+        self.groups.get(fingerprint)
+    }
+
+    /// All groups, most recently seen first
+    pub fn all(&self) -> Vec<ExceptionGroup> {
+        let mut groups: Vec<ExceptionGroup> = self.groups.values().cloned().collect();
+        groups.sort_by(|a, b| {
+            let a_last = a.occurrence_ids.last().copied().unwrap_or(0);
+            let b_last = b.occurrence_ids.last().copied().unwrap_or(0);
+            b_last.cmp(&a_last)
+        });
+        groups
+    }
+
+    pub fn clear(&mut self) {
+        self.groups.clear();
+    }
+}
+
+/// Parse the typed exception out of a stored entry
+pub fn parse_exception(entry: &EventEntry) -> Option<ExceptionEvent> {
+    if entry.event_type != "exception" {
+        return None;
+    }
+    let content = entry.raw_payload.get("content")?;
+    match process_exception_event(content) {
+        Ok(ProcessedEvent::Exception(exception)) => Some(exception),
+        _ => None,
+    }
+}
+
+/// Fingerprint an exception by its class and top application frames.
+///
+/// The message is deliberately left out since it often embeds ids or values
+/// that differ between otherwise identical failures.
+pub fn fingerprint(exception: &ExceptionEvent) -> String {
+    let mut hasher = DefaultHasher::new();
+    exception.class.hash(&mut hasher);
+
+    let app_frames: Vec<&StackFrame> = exception
+        .stack_trace
+        .iter()
+        .filter(|frame| !is_vendor_frame(frame))
+        .take(FINGERPRINT_FRAMES)
+        .collect();
+
+    if app_frames.is_empty() {
+        // No usable frames - fall back to the throw site
+        exception.file.hash(&mut hasher);
+        exception.line.hash(&mut hasher);
+    } else {
+        for frame in app_frames {
+            frame.file.hash(&mut hasher);
+            frame.line.hash(&mut hasher);
+            frame.function.hash(&mut hasher);
+        }
+    }
+
+    format!("{:016x}", hasher.finish())
+}
+
+fn is_vendor_frame(frame: &StackFrame) -> bool {
+    config().frames.classify(frame) == FrameKind::Vendor
+}
+
+/// Text used to compare two occurrences: stack frames followed by the context
+pub fn occurrence_text(exception: &ExceptionEvent) -> String {
+    let mut lines: Vec<String> = exception
+        .stack_trace
+        .iter()
+        .map(|frame| {
+            let class = frame.class.as_deref().unwrap_or("");
+            format!("{class}::{} {}:{}", frame.function, frame.file, frame.line)
+        })
+        .collect();
+
+    if let Some(context) = &exception.context {
+        lines.push(serde_json::to_string_pretty(context).unwrap_or_default());
+    }
+
+    lines.join("\n")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffKind {
+    Same,
+    Added,
+    Removed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub text: String,
+}
+
+/// Line-based LCS diff between two occurrences
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // Guard against quadratic blowup on huge contexts
+    if old.len() * new.len() > 1_000_000 {
+        return old
+            .iter()
+            .map(|line| DiffLine {
+                kind: DiffKind::Removed,
+                text: line.to_string(),
+            })
+            .chain(new.iter().map(|line| DiffLine {
+                kind: DiffKind::Added,
+                text: line.to_string(),
+            }))
+            .collect();
+    }
+
+    // lcs[i][j] = length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            result.push(DiffLine {
+                kind: DiffKind::Same,
+                text: old[i].to_string(),
+            });
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            result.push(DiffLine {
+                kind: DiffKind::Removed,
+                text: old[i].to_string(),
+            });
+            i += 1;
+        } else {
+            result.push(DiffLine {
+                kind: DiffKind::Added,
+                text: new[j].to_string(),
+            });
+            j += 1;
+        }
+    }
+    result.extend(old[i..].iter().map(|line| DiffLine {
+        kind: DiffKind::Removed,
+        text: line.to_string(),
+    }));
+    result.extend(new[j..].iter().map(|line| DiffLine {
+        kind: DiffKind::Added,
+        text: line.to_string(),
+    }));
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn exception_entry(id: u64, message: &str, frame_line: u64) -> EventEntry {
+        EventEntry {
+            id,
+            timestamp: format!("2024-01-01 00:00:0{id}"),
+            label: "Exception".to_string(),
+            description: String::new(),
+            content_type: "custom_ui".to_string(),
+            event_type: "exception".to_string(),
+            project: None,
+            raw_payload: json!({
+                "type": "exception",
+                "content": {
+                    "class": "RuntimeException",
+                    "message": message,
+                    "frames": [
+                        {"file_name": "/app/vendor/laravel/Router.php", "line_number": 10, "method": "dispatch"},
+                        {"file_name": "/app/Http/Controllers/UserController.php", "line_number": frame_line, "method": "show"}
+                    ]
+                }
+            }),
+        }
+    }
+
+    #[test]
+    fn test_groups_ignore_message_differences() {
+        let mut groups = ExceptionGroups::new();
+        let first = groups.record(&exception_entry(1, "User 1 not found", 42));
+        let second = groups.record(&exception_entry(2, "User 2 not found", 42));
+
+        assert_eq!(first, second);
+        let group = groups.get(&first.unwrap()).unwrap();
+        assert_eq!(group.count, 2);
+        assert_eq!(group.occurrence_ids, vec![1, 2]);
+        assert_eq!(group.first_seen, "2024-01-01 00:00:01");
+        assert_eq!(group.last_seen, "2024-01-01 00:00:02");
+        assert_eq!(group.message, "User 2 not found");
+
+        let fingerprint = group.fingerprint.clone();
+        groups.forget(&exception_entry(1, "User 1 not found", 42));
+        let group = groups.get(&fingerprint).unwrap();
+        assert_eq!((group.count, group.occurrence_ids.clone()), (2, vec![2]));
+        groups.forget(&exception_entry(2, "User 2 not found", 42));
+        assert!(groups.get(&fingerprint).is_none());
+    }
+
+    #[test]
+    fn test_groups_split_on_application_frames() {
+        let mut groups = ExceptionGroups::new();
+        let first = groups.record(&exception_entry(1, "boom", 42));
+        let second = groups.record(&exception_entry(2, "boom", 43));
+
+        assert_ne!(first, second);
+        assert_eq!(groups.all().len(), 2);
+        assert_eq!(groups.all()[0].occurrence_ids, vec![2]);
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let diff = diff_lines("a\nb\nc", "a\nx\nc");
+        let kinds: Vec<DiffKind> = diff.iter().map(|line| line.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                DiffKind::Same,
+                DiffKind::Removed,
+                DiffKind::Added,
+                DiffKind::Same
+            ]
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use crate::events::types::StackFrame;
+use std::ops::Range;
+
+/// Path fragments treated as vendor code when `RAY_VENDOR_PATTERNS` is not set
+const DEFAULT_VENDOR_PATTERNS: &[&str] = &["/vendor/", "/node_modules/"];
+
+/// Settings for splitting stack traces into application and vendor frames
+#[derive(Clone, Debug)]
+pub struct FrameConfig {
+    pub vendor_patterns: Vec<String>,
+}
+
+impl Default for FrameConfig {
+    fn default() -> Self {
+        Self {
+            vendor_patterns: DEFAULT_VENDOR_PATTERNS
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl FrameConfig {
+    /// Read `RAY_VENDOR_PATTERNS` (comma-separated path fragments)
+    pub fn from_env() -> Self {
+        match std::env::var("RAY_VENDOR_PATTERNS") {
+            Ok(raw) => Self {
+                vendor_patterns: raw
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty())
+                    .map(|p| p.to_string())
+                    .collect(),
+            },
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn classify(&self, frame: &StackFrame) -> FrameKind {
+        // Trust the client when it tells us explicitly
+        if let Some(vendor) = frame.vendor_frame {
+            return if vendor {
+                FrameKind::Vendor
+            } else {
+                FrameKind::Application
+            };
+        }
+
+        if self
+            .vendor_patterns
+            .iter()
+            .any(|pattern| frame.file.contains(pattern.as_str()))
+        {
+            FrameKind::Vendor
+        } else {
+            FrameKind::Application
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameKind {
+    Application,
+    Vendor,
+}
+
+/// A run of frames as displayed: single application frames, or folded vendor runs
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FrameSegment {
+    Application(usize),
+    Vendor(Range<usize>),
+}
+
+/// Split classified frames into segments, folding consecutive vendor frames
+pub fn segment_frames(kinds: &[FrameKind]) -> Vec<FrameSegment> {
+    let mut segments = Vec::new();
+    let mut index = 0;
+
+    while index < kinds.len() {
+        match kinds[index] {
+            FrameKind::Application => {
+                segments.push(FrameSegment::Application(index));
+                index += 1;
+            }
+            FrameKind::Vendor => {
+                let start = index;
+                while index < kinds.len() && kinds[index] == FrameKind::Vendor {
+                    index += 1;
+                }
+                segments.push(FrameSegment::Vendor(start..index));
+            }
+        }
+    }
+
+    segments
+}
+
+/// Index of the first application frame, i.e. the most likely culprit
+pub fn first_application_frame(kinds: &[FrameKind]) -> Option<usize> {
+    kinds
+        .iter()
+        .position(|kind| *kind == FrameKind::Application)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(file: &str, vendor_frame: Option<bool>) -> StackFrame {
+        StackFrame {
+            file: file.to_string(),
+            line: 1,
+            function: "handle".to_string(),
+            class: None,
+            vendor_frame,
+            snippet: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_classify_uses_patterns_and_client_hint() {
+        let config = FrameConfig::default();
+        assert_eq!(
+            config.classify(&frame("/app/vendor/laravel/Router.php", None)),
+            FrameKind::Vendor
+        );
+        assert_eq!(
+            config.classify(&frame("/app/Http/Kernel.php", None)),
+            FrameKind::Application
+        );
+        assert_eq!(
+            config.classify(&frame("/app/vendor/laravel/Router.php", Some(false))),
+            FrameKind::Application
+        );
+    }
+
+    #[test]
+    fn test_segment_frames_folds_vendor_runs() {
+        use FrameKind::{Application as A, Vendor as V};
+        let kinds = [V, V, A, V, A, A, V];
+
+        assert_eq!(
+            segment_frames(&kinds),
+            vec![
+                FrameSegment::Vendor(0..2),
+                FrameSegment::Application(2),
+                FrameSegment::Vendor(3..4),
+                FrameSegment::Application(4),
+                FrameSegment::Application(5),
+                FrameSegment::Vendor(6..7),
+            ]
+        );
+        assert_eq!(first_application_frame(&kinds), Some(2));
+    }
+}
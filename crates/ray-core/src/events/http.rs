@@ -0,0 +1,43 @@
+use crate::events::base::{extract_timestamp, EventEntry};
+use crate::events::processors::http::process_http_event;
+use crate::events::types::{HttpEventType, ProcessedEvent};
+use anyhow::Result;
+use serde_json::Value;
+
+pub fn process(payload: &Value) -> Result<EventEntry> {
+    let mut entry = EventEntry {
+        id: 0,
+        timestamp: extract_timestamp(payload),
+        label: "request".to_string(),
+        description: String::new(),
+        content_type: "custom_ui".to_string(),
+        event_type: "request".to_string(),
+        project: None,
+        raw_payload: payload.clone(),
+    };
+
+    if let Some(content) = payload.get("content") {
+        // Process using the new architecture
+        let processed_event = process_http_event(content)?;
+
+        // Set labels and descriptions based on processed event
+        if let ProcessedEvent::Http(ref http_event) = processed_event {
+            entry.label = match http_event.event_type {
+                HttpEventType::Request => "HTTP Request".to_string(),
+                HttpEventType::Response => "HTTP Response".to_string(),
+            };
+            let method_or_status = match http_event.event_type {
+                HttpEventType::Request => http_event.method.as_deref().unwrap_or("GET").to_string(),
+                HttpEventType::Response => http_event
+                    .status_code
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| "Response".to_string()),
+            };
+            entry.description = format!("{} {}", method_or_status, http_event.url);
+        } else {
+            return Err(anyhow::anyhow!("Unexpected event type from http processor"));
+        }
+    }
+
+    Ok(entry)
+}
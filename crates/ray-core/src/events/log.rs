@@ -0,0 +1,43 @@
+use crate::events::base::{extract_timestamp, truncate, EventEntry};
+use crate::events::processors::process_log_event;
+use crate::events::types::ProcessedEvent;
+use anyhow::Result;
+use serde_json::Value;
+
+pub fn process(payload: &Value) -> Result<EventEntry> {
+    let mut entry = EventEntry {
+        id: 0,
+        timestamp: extract_timestamp(payload),
+        label: "log".to_string(),
+        description: String::new(),
+        content_type: "custom_ui".to_string(),
+        event_type: "log".to_string(),
+        project: None,
+        raw_payload: payload.clone(),
+    };
+
+    if let Some(content) = payload.get("content") {
+        // Process using the new architecture
+        let processed_event = process_log_event(content)?;
+
+        // Set labels and descriptions based on processed event
+        if let ProcessedEvent::Log(ref log_event) = processed_event {
+            entry.label = "Log".to_string();
+            let description = if log_event.message.len() > 100 {
+                format!("{}...", truncate(&log_event.message, 97))
+            } else {
+                log_event.message.clone()
+            };
+            // Clean up any JSON formatting for the description
+            entry.description = description
+                .replace('\n', " ")
+                .replace("  ", " ")
+                .trim()
+                .to_string();
+        } else {
+            return Err(anyhow::anyhow!("Unexpected event type from log processor"));
+        }
+    }
+
+    Ok(entry)
+}
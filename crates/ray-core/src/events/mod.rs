@@ -0,0 +1,165 @@
+use anyhow::Result;
+use serde_json::Value;
+
+pub mod application_log;
+pub mod base;
+pub mod cache;
+pub mod cache_timeline;
+pub mod custom;
+pub mod event_type;
+pub mod exception;
+pub mod exception_groups;
+pub mod frames;
+pub mod http;
+pub mod log;
+pub mod processors;
+pub mod query;
+pub mod registry;
+// pub mod table; // Removed - was an anti-pattern dispatcher
+pub mod types;
+
+pub use base::{EventEntry, EventProcessor};
+pub use event_type::EventType;
+pub use registry::{registry, EventRegistry};
+
+/// Look up the processor registered for the given event type
+pub fn create_processor(event_type: &str) -> Option<EventProcessor> {
+    registry().processor(event_type)
+}
+
+/// Whether a processor exists for the type, rather than it showing up as an unknown event
+pub fn is_known_type(event_type: &str) -> bool {
+    // Table events are always mapped to one of the built-in types
+    event_type == "table" || create_processor(event_type).is_some()
+}
+
+/// Process an event with the appropriate processor
+pub fn process_event(event_type: &str, payload: &Value) -> Result<EventEntry> {
+    // Smart detection: if event_type is "table", check content.label to determine actual type
+    let actual_event_type = if event_type == "table" {
+        detect_table_event_type(payload)
+    } else {
+        event_type.to_string()
+    };
+
+    match create_processor(&actual_event_type) {
+        Some(processor) => processor.process(payload),
+        None => Ok(EventEntry {
+            id: 0,
+            timestamp: String::new(),
+            label: format!("Unknown Event: {}", actual_event_type),
+            description: "Unknown event type".to_string(),
+            content_type: "json".to_string(),
+            event_type: actual_event_type,
+            project: None,
+            raw_payload: payload.clone(),
+        }),
+    }
+}
+
+/// Detect the actual event type from table events based on content.label
+fn detect_table_event_type(payload: &Value) -> String {
+    if let Some(content) = payload.get("content") {
+        if let Some(label) = content.get("label").and_then(|l| l.as_str()) {
+            match label.to_lowercase().as_str() {
+                "http" | "request" => "request".to_string(),
+                "cache" => "cache".to_string(),
+                "query" | "database" => "query".to_string(),
+                "log" => "log".to_string(),
+                "exception" | "error" => "exception".to_string(),
+                _ => "request".to_string(), // Default to request for unknown labels
+            }
+        } else {
+            "request".to_string() // Default fallback
+        }
+    } else {
+        "request".to_string() // Default fallback
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_table_http_detection() {
+        let table_event = json!({
+            "type": "table",
+            "content": {
+                "label": "Http",
+                "values": {
+                    "Method": "GET",
+                    "URL": "https://api.example.com/test"
+                }
+            }
+        });
+
+        let result = process_event("table", &table_event).unwrap();
+        assert_eq!(result.event_type, "request");
+        assert!(result.label.contains("HTTP"));
+    }
+
+    #[test]
+    fn test_table_cache_detection() {
+        let table_event = json!({
+            "type": "table",
+            "content": {
+                "label": "Cache",
+                "values": {
+                    "Event": "Hit",
+                    "Key": "test:key"
+                }
+            }
+        });
+
+        let result = process_event("table", &table_event).unwrap();
+        assert_eq!(result.event_type, "cache");
+        assert!(result.label.contains("Cache"));
+    }
+
+    #[test]
+    fn test_process_application_log() {
+        let test_event = json!({
+            "type": "application_log",
+            "content": {
+                "value": "Test application log message"
+            }
+        });
+
+        let result = process_event("application_log", &test_event).unwrap();
+        assert_eq!(result.label, "Application Log");
+        assert_eq!(result.description, "Test application log message");
+        assert_eq!(result.content_type, "custom_ui");
+    }
+
+    #[test]
+    fn test_process_log_event() {
+        let test_event = json!({
+            "type": "log",
+            "content": {
+                "values": ["Test log message", "Another value"]
+            }
+        });
+
+        let result = process_event("log", &test_event).unwrap();
+        assert_eq!(result.label, "Log");
+        assert_eq!(result.description, "Test log message");
+        assert_eq!(result.content_type, "custom_ui");
+    }
+
+    #[test]
+    fn test_descriptions_truncate_on_char_boundary() {
+        // Byte 50 falls inside the first "é"
+        let sql = format!("{}{}", "a".repeat(49), "é".repeat(4));
+        let test_event = json!({"type": "query", "content": {"sql": sql}});
+
+        let result = process_event("query", &test_event).unwrap();
+        assert_eq!(result.description, format!("{}...", "a".repeat(49)));
+
+        let message = "é".repeat(60);
+        let test_event = json!({"type": "log", "content": {"values": [message]}});
+        let result = process_event("log", &test_event).unwrap();
+        assert_eq!(result.description, format!("{}...", "é".repeat(48)));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::events::types::{ExceptionEvent, ProcessedEvent, StackFrame};
+use crate::events::types::{ExceptionEvent, ProcessedEvent, SnippetLine, StackFrame};
 use anyhow::Result;
 use serde_json::Value;
 
@@ -28,14 +28,21 @@ pub fn process_exception_event(content: &Value) -> Result<ProcessedEvent> {
     if let Some(frames) = content.get("frames").and_then(Value::as_array) {
         for frame in frames {
             if let Some(frame_obj) = frame.as_object() {
+                // Ray sends file_name/line_number/method, other clients file/line/function
                 let frame_file = frame_obj
-                    .get("file")
+                    .get("file_name")
+                    .or_else(|| frame_obj.get("file"))
                     .and_then(Value::as_str)
                     .unwrap_or_default()
                     .to_string();
-                let frame_line = frame_obj.get("line").and_then(Value::as_u64).unwrap_or(0);
+                let frame_line = frame_obj
+                    .get("line_number")
+                    .or_else(|| frame_obj.get("line"))
+                    .and_then(Value::as_u64)
+                    .unwrap_or(0);
                 let function = frame_obj
-                    .get("function")
+                    .get("method")
+                    .or_else(|| frame_obj.get("function"))
                     .and_then(Value::as_str)
                     .unwrap_or_default()
                     .to_string();
@@ -43,12 +50,20 @@ pub fn process_exception_event(content: &Value) -> Result<ProcessedEvent> {
                     .get("class")
                     .and_then(Value::as_str)
                     .map(|s| s.to_string());
+                let vendor_frame = frame_obj.get("vendor_frame").and_then(Value::as_bool);
+                let snippet = frame_obj
+                    .get("snippet")
+                    .and_then(Value::as_array)
+                    .map(|lines| lines.iter().filter_map(parse_snippet_line).collect())
+                    .unwrap_or_default();
 
                 stack_trace.push(StackFrame {
                     file: frame_file,
                     line: frame_line,
                     function,
                     class,
+                    vendor_frame,
+                    snippet,
                 });
             }
         }
@@ -65,3 +80,14 @@ pub fn process_exception_event(content: &Value) -> Result<ProcessedEvent> {
         context,
     }))
 }
+
+fn parse_snippet_line(line: &Value) -> Option<SnippetLine> {
+    Some(SnippetLine {
+        line_number: line.get("line_number").and_then(Value::as_u64)?,
+        text: line
+            .get("text")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+    })
+}
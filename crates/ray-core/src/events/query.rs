@@ -0,0 +1,53 @@
+use crate::events::base::{extract_timestamp, truncate, EventEntry};
+use crate::events::processors::process_query_event;
+use crate::events::types::ProcessedEvent;
+use anyhow::Result;
+use serde_json::Value;
+
+pub fn process(payload: &Value) -> Result<EventEntry> {
+    let mut entry = EventEntry {
+        id: 0,
+        timestamp: extract_timestamp(payload),
+        label: "Query".to_string(),
+        description: String::new(),
+        content_type: "custom_ui".to_string(),
+        event_type: "query".to_string(),
+        project: None,
+        raw_payload: payload.clone(),
+    };
+
+    if let Some(content) = payload.get("content") {
+        // Process using the new architecture
+        let processed_event = process_query_event(content)?;
+
+        // Set labels and descriptions based on processed event
+        if let ProcessedEvent::Query(ref query_event) = processed_event {
+            // Extract the SQL operation type (SELECT, INSERT, UPDATE, etc.)
+            let operation_type =
+                if let Some(first_word) = query_event.sql.split_whitespace().next() {
+                    first_word.to_uppercase()
+                } else {
+                    "SQL".to_string()
+                };
+            entry.label = format!("Query: {operation_type}");
+
+            let description_sql = if query_event.sql.len() > 50 {
+                format!("{}...", truncate(&query_event.sql, 50).trim())
+            } else {
+                query_event.sql.trim().to_string()
+            };
+
+            if let Some(time) = query_event.duration_ms {
+                entry.description = format!("{description_sql} ({time}ms)");
+            } else {
+                entry.description = description_sql;
+            }
+        } else {
+            return Err(anyhow::anyhow!(
+                "Unexpected event type from query processor"
+            ));
+        }
+    }
+
+    Ok(entry)
+}
@@ -0,0 +1,254 @@
+use crate::events::base::EventProcessor;
+use crate::events::custom::{CustomTypeDefinition, CustomTypesConfig};
+use crate::events::event_type::EventType;
+use crate::events::{application_log, cache, exception, http, log, query};
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// An event type added at runtime, listed after the built-in ones
+#[derive(Clone, Debug)]
+struct CustomType {
+    name: &'static str,
+    display_name: &'static str,
+    aliases: Vec<String>,
+}
+
+/// Processors keyed by payload type name, plus the custom event types they produce
+pub struct EventRegistry {
+    processors: Mutex<HashMap<String, EventProcessor>>,
+    custom_types: Mutex<Vec<CustomType>>,
+    definitions: Mutex<HashMap<String, Arc<CustomTypeDefinition>>>,
+    load_errors: Vec<String>,
+}
+
+impl EventRegistry {
+    pub fn new() -> Self {
+        Self {
+            processors: Mutex::new(HashMap::new()),
+            custom_types: Mutex::new(Vec::new()),
+            definitions: Mutex::new(HashMap::new()),
+            load_errors: Vec::new(),
+        }
+    }
+
+    /// Registry with processors for the payload types Ray sends out of the box
+    pub fn with_builtins() -> Self {
+        let registry = Self::new();
+        let builtins: [(&[&str], EventProcessor); 6] = [
+            (&["log"], EventProcessor::new(log::process)),
+            (&["exception"], EventProcessor::new(exception::process)),
+            (
+                &["query", "executed_query"],
+                EventProcessor::new(query::process),
+            ),
+            (
+                &["application_log"],
+                EventProcessor::new(application_log::process),
+            ),
+            (&["cache"], EventProcessor::new(cache::process)),
+            (&["request"], EventProcessor::new(http::process)),
+        ];
+
+        for (names, processor) in builtins {
+            for name in names {
+                registry.register_processor(name, processor.clone());
+            }
+        }
+        registry
+    }
+
+    /// Add or replace the processor for a payload type name
+    pub fn register_processor(&self, name: &str, processor: EventProcessor) {
+        self.processors
+            .lock()
+            .unwrap()
+            .insert(name.to_string(), processor);
+    }
+
+    /// Add a new event type with its processor, so it can be filtered like the built-in ones
+    pub fn register_type(
+        &self,
+        name: &str,
+        display_name: &str,
+        aliases: &[&str],
+        processor: EventProcessor,
+    ) -> Result<EventType> {
+        if let Some(taken) = std::iter::once(&name)
+            .chain(aliases)
+            .find(|name| EventType::builtin(name).is_some())
+        {
+            bail!("{taken} is a built-in event type");
+        }
+
+        let name = {
+            let mut custom_types = self.custom_types.lock().unwrap();
+            let custom_type = match custom_types.iter_mut().find(|t| t.name == name) {
+                Some(existing) => existing,
+                None => {
+                    // Leaked once per type so `EventType` can stay `Copy`
+                    custom_types.push(CustomType {
+                        name: Box::leak(name.into()),
+                        display_name: "",
+                        aliases: Vec::new(),
+                    });
+                    custom_types.last_mut().unwrap()
+                }
+            };
+            if custom_type.display_name != display_name {
+                custom_type.display_name = Box::leak(display_name.into());
+            }
+            custom_type.aliases = aliases.iter().map(|alias| alias.to_string()).collect();
+            custom_type.name
+        };
+
+        for type_name in std::iter::once(name).chain(aliases.iter().copied()) {
+            self.register_processor(type_name, processor.clone());
+        }
+        Ok(EventType::Custom(name))
+    }
+
+    /// Register a declarative type from the custom types file
+    pub fn register_definition(&self, definition: CustomTypeDefinition) -> Result<EventType> {
+        let definition = Arc::new(definition);
+        let aliases: Vec<&str> = definition.aliases.iter().map(String::as_str).collect();
+        let processor = {
+            let definition = Arc::clone(&definition);
+            EventProcessor::new(move |payload| definition.process(payload))
+        };
+
+        let event_type = self.register_type(
+            &definition.name,
+            &definition.display_name,
+            &aliases,
+            processor,
+        )?;
+        self.definitions
+            .lock()
+            .unwrap()
+            .insert(definition.name.clone(), Arc::clone(&definition));
+        Ok(event_type)
+    }
+
+    pub fn processor(&self, name: &str) -> Option<EventProcessor> {
+        self.processors.lock().unwrap().get(name).cloned()
+    }
+
+    /// Custom type registered under the name or one of its aliases
+    pub fn event_type(&self, name: &str) -> Option<EventType> {
+        self.custom_types
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|t| t.name == name || t.aliases.iter().any(|alias| alias == name))
+            .map(|t| EventType::Custom(t.name))
+    }
+
+    pub fn custom_types(&self) -> Vec<EventType> {
+        self.custom_types
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|t| EventType::Custom(t.name))
+            .collect()
+    }
+
+    pub fn display_name(&self, name: &str) -> Option<&'static str> {
+        self.custom_types
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|t| t.name == name)
+            .map(|t| t.display_name)
+    }
+
+    pub fn aliases(&self, name: &str) -> Vec<String> {
+        self.custom_types
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|t| t.name == name)
+            .map(|t| t.aliases.clone())
+            .unwrap_or_default()
+    }
+
+    /// Field mappings of a declarative type, for front-ends to display
+    pub fn definition(&self, name: &str) -> Option<Arc<CustomTypeDefinition>> {
+        self.definitions.lock().unwrap().get(name).cloned()
+    }
+
+    /// Problems with the custom types file, for storage to log at startup
+    pub fn load_errors(&self) -> &[String] {
+        &self.load_errors
+    }
+}
+
+impl Default for EventRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static REGISTRY: OnceLock<EventRegistry> = OnceLock::new();
+
+/// Global registry: the built-in processors plus the types from `RAY_CUSTOM_TYPES`
+///
+/// The file is read here rather than through `config()`, because parsing alert
+/// rules while the config loads needs the registry to resolve custom type names.
+pub fn registry() -> &'static EventRegistry {
+    REGISTRY.get_or_init(|| {
+        let custom_types = CustomTypesConfig::from_env();
+        let mut registry = EventRegistry::with_builtins();
+        registry.load_errors = custom_types.errors;
+
+        for definition in custom_types.definitions {
+            if let Err(e) = registry.register_definition(definition) {
+                registry.load_errors.push(e.to_string());
+            }
+        }
+        registry
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_register_type() {
+        let registry = EventRegistry::with_builtins();
+        assert!(registry.processor("executed_query").is_some());
+        assert!(registry.processor("feature_flag").is_none());
+
+        let processor = EventProcessor::new(|payload| {
+            let mut entry = log::process(payload)?;
+            entry.event_type = "queue_metrics".to_string();
+            Ok(entry)
+        });
+        let event_type = registry
+            .register_type("queue_metrics", "Queue Metrics", &["queue"], processor)
+            .unwrap();
+
+        assert_eq!(event_type.as_str(), "queue_metrics");
+        assert_eq!(registry.event_type("queue"), Some(event_type));
+        assert_eq!(
+            registry.display_name("queue_metrics"),
+            Some("Queue Metrics")
+        );
+        assert_eq!(registry.custom_types(), vec![event_type]);
+
+        let entry = registry
+            .processor("queue")
+            .unwrap()
+            .process(&json!({"content": {"values": ["depth 12"]}}))
+            .unwrap();
+        assert_eq!(entry.event_type, "queue_metrics");
+
+        // Built-in names can only get a different processor, not a second type
+        let processor = registry.processor("log").unwrap();
+        assert!(registry
+            .register_type("flag", "Flag", &["request"], processor)
+            .is_err());
+    }
+}
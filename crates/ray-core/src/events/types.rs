@@ -86,6 +86,15 @@ pub struct StackFrame {
     pub line: u64,
     pub function: String,
     pub class: Option<String>,
+    /// Client-side vendor hint (Ray's `vendor_frame`), if sent
+    pub vendor_frame: Option<bool>,
+    pub snippet: Vec<SnippetLine>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SnippetLine {
+    pub line_number: u64,
+    pub text: String,
 }
 
 #[derive(Clone, Debug)]
@@ -0,0 +1,16 @@
+// lib.rs - UI-agnostic core: event processing, storage, filtering and the HTTP server
+pub mod config;
+pub mod editor;
+pub mod events;
+pub mod event_storage;
+pub mod event_filter;
+pub mod metrics;
+pub mod performance;
+pub mod server;
+pub mod notifications;
+pub mod redaction;
+pub mod scripts;
+
+// Re-export commonly used items
+pub use events::{process_event, EventEntry};
+pub use event_storage::EventStorage;
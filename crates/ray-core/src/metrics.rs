@@ -0,0 +1,249 @@
+// metrics.rs - health counters for the server, exposed at /metrics in the Prometheus text format
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Upper bounds of the processing latency buckets, in seconds
+const LATENCY_BUCKETS: [f64; 10] = [
+    0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.1, 1.0,
+];
+
+/// Counts of observed durations per latency bucket
+#[derive(Debug, Default)]
+pub struct Histogram {
+    /// Observations at or below each bound; the cumulative counts are summed when rendering
+    buckets: [AtomicU64; LATENCY_BUCKETS.len()],
+    count: AtomicU64,
+    sum_nanos: AtomicU64,
+}
+
+impl Histogram {
+    pub fn observe(&self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        if let Some(index) = LATENCY_BUCKETS.iter().position(|bound| seconds <= *bound) {
+            self.buckets[index].fetch_add(1, Ordering::Relaxed);
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_nanos
+            .fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+    }
+}
+
+/// Counters and gauges updated by the server and the storage as they work
+///
+/// Everything is cheap to update from any thread; payload types are the only
+/// labels, and unknown ones share a single `other` label so clients can't grow
+/// the output without bound.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    requests: AtomicU64,
+    payloads: Mutex<BTreeMap<String, u64>>,
+    failures: AtomicU64,
+    processing: Histogram,
+    evictions: AtomicU64,
+    connections: AtomicUsize,
+    stored_events: AtomicUsize,
+    stored_bytes: AtomicU64,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_request(&self) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a received payload; callers pass `other` for types nothing handles
+    pub fn record_payload(&self, event_type: &str) {
+        let mut payloads = self.payloads.lock().unwrap();
+        match payloads.get_mut(event_type) {
+            Some(count) => *count += 1,
+            None => {
+                payloads.insert(event_type.to_string(), 1);
+            }
+        }
+    }
+
+    pub fn record_failure(&self) {
+        self.failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Time spent running scripts, redaction and processing on one payload
+    pub fn record_processing(&self, duration: Duration) {
+        self.processing.observe(duration);
+    }
+
+    pub fn record_evictions(&self, count: usize) {
+        self.evictions.fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    pub fn connection_opened(&self) {
+        self.connections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn connection_closed(&self) {
+        self.connections.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Current number of stored events and their approximate size
+    pub fn set_storage(&self, events: usize, bytes: u64) {
+        self.stored_events.store(events, Ordering::Relaxed);
+        self.stored_bytes.store(bytes, Ordering::Relaxed);
+    }
+
+    /// Write every metric family in the text exposition format
+    pub fn render(&self, out: &mut Exposition) {
+        out.counter(
+            "ray_http_requests_total",
+            "HTTP requests received",
+            self.requests.load(Ordering::Relaxed),
+        );
+        let payloads = self.payloads.lock().unwrap().clone();
+        out.labeled_counter(
+            "ray_payloads_total",
+            "Payloads received, by type",
+            "type",
+            payloads
+                .iter()
+                .map(|(event_type, count)| (event_type.as_str(), *count)),
+        );
+        out.counter(
+            "ray_payload_failures_total",
+            "Payloads that could not be processed",
+            self.failures.load(Ordering::Relaxed),
+        );
+        out.histogram(
+            "ray_processing_duration_seconds",
+            "Time to process one payload",
+            &self.processing,
+        );
+        out.gauge(
+            "ray_stored_events",
+            "Events currently stored",
+            self.stored_events.load(Ordering::Relaxed) as u64,
+        );
+        out.gauge(
+            "ray_stored_bytes",
+            "Approximate size of the stored events",
+            self.stored_bytes.load(Ordering::Relaxed),
+        );
+        out.counter(
+            "ray_evictions_total",
+            "Events removed to stay under RAY_MAX_EVENTS",
+            self.evictions.load(Ordering::Relaxed),
+        );
+        out.gauge(
+            "ray_active_connections",
+            "Open client connections",
+            self.connections.load(Ordering::Relaxed) as u64,
+        );
+    }
+}
+
+/// Prometheus text output, built one metric family at a time
+#[derive(Debug, Default)]
+pub struct Exposition {
+    text: String,
+}
+
+impl Exposition {
+    /// Value of the `Content-Type` header for the output
+    pub const CONTENT_TYPE: &'static str = "text/plain; version=0.0.4; charset=utf-8";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn counter(&mut self, name: &str, help: &str, value: u64) {
+        self.header(name, help, "counter");
+        let _ = writeln!(self.text, "{name} {value}");
+    }
+
+    pub fn gauge(&mut self, name: &str, help: &str, value: u64) {
+        self.header(name, help, "gauge");
+        let _ = writeln!(self.text, "{name} {value}");
+    }
+
+    pub fn labeled_counter<'a>(
+        &mut self,
+        name: &str,
+        help: &str,
+        label: &str,
+        values: impl IntoIterator<Item = (&'a str, u64)>,
+    ) {
+        self.header(name, help, "counter");
+        for (value, count) in values {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            let _ = writeln!(self.text, "{name}{{{label}=\"{value}\"}} {count}");
+        }
+    }
+
+    pub fn histogram(&mut self, name: &str, help: &str, histogram: &Histogram) {
+        self.header(name, help, "histogram");
+        let mut cumulative = 0;
+        for (bound, bucket) in LATENCY_BUCKETS.iter().zip(&histogram.buckets) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let _ = writeln!(self.text, "{name}_bucket{{le=\"{bound}\"}} {cumulative}");
+        }
+        let count = histogram.count.load(Ordering::Relaxed);
+        let sum = histogram.sum_nanos.load(Ordering::Relaxed) as f64 / 1e9;
+        let _ = writeln!(self.text, "{name}_bucket{{le=\"+Inf\"}} {count}");
+        let _ = writeln!(self.text, "{name}_sum {sum}");
+        let _ = writeln!(self.text, "{name}_count {count}");
+    }
+
+    pub fn finish(self) -> String {
+        self.text
+    }
+
+    fn header(&mut self, name: &str, help: &str, kind: &str) {
+        let _ = writeln!(self.text, "# HELP {name} {help}");
+        let _ = writeln!(self.text, "# TYPE {name} {kind}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_exposition() {
+        let metrics = Metrics::new();
+        metrics.record_request();
+        metrics.record_payload("log");
+        metrics.record_payload("log");
+        metrics.record_payload("other");
+        metrics.record_processing(Duration::from_micros(300));
+        metrics.record_processing(Duration::from_secs(2));
+        metrics.record_evictions(3);
+        metrics.set_storage(10, 2048);
+
+        let mut out = Exposition::new();
+        metrics.render(&mut out);
+        let text = out.finish();
+
+        for line in [
+            "# TYPE ray_http_requests_total counter",
+            "ray_http_requests_total 1",
+            "ray_payloads_total{type=\"log\"} 2",
+            "ray_payloads_total{type=\"other\"} 1",
+            "ray_processing_duration_seconds_bucket{le=\"0.00025\"} 0",
+            "ray_processing_duration_seconds_bucket{le=\"0.0005\"} 1",
+            "ray_processing_duration_seconds_bucket{le=\"1\"} 1",
+            "ray_processing_duration_seconds_bucket{le=\"+Inf\"} 2",
+            "ray_processing_duration_seconds_count 2",
+            "ray_stored_bytes 2048",
+            "ray_evictions_total 3",
+            "ray_active_connections 0",
+        ] {
+            assert!(
+                text.lines().any(|l| l == line),
+                "missing {line} in:\n{text}"
+            );
+        }
+    }
+}
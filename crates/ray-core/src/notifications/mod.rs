@@ -0,0 +1,320 @@
+pub mod rules;
+pub mod sinks;
+
+use crate::events::EventEntry;
+use anyhow::Result;
+use chrono::Local;
+use rules::{parse_rules, AlertRule, RateLimiter};
+use serde_json::{json, Value};
+use sinks::{TerminalSink, ToastQueue, ToastSink, WebhookSink};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Rate limit key shared by all Ray `notify` payloads
+const NOTIFY_RULE: &str = "notify";
+
+/// Something worth interrupting the user for
+#[derive(Clone, Debug)]
+pub struct Notification {
+    pub title: String,
+    pub body: String,
+    pub timestamp: String,
+    /// Stored entry that triggered the notification
+    pub event_id: Option<u64>,
+    /// Alert rule that matched, `None` for Ray `notify` payloads
+    pub rule: Option<String>,
+}
+
+impl Notification {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "title": self.title,
+            "body": self.body,
+            "timestamp": self.timestamp,
+            "event_id": self.event_id,
+            "rule": self.rule,
+        })
+    }
+}
+
+/// Destination for notifications
+pub trait NotificationSink: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn send(&self, notification: &Notification) -> Result<()>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SinkKind {
+    Toast,
+    Terminal,
+    Webhook,
+}
+
+/// Settings for alert rules and notification sinks
+#[derive(Clone, Debug)]
+pub struct NotificationConfig {
+    pub sinks: Vec<SinkKind>,
+    pub webhook_url: Option<String>,
+    pub rules: Vec<AlertRule>,
+    /// Rules from `RAY_ALERT_RULES` that failed to parse
+    pub rule_errors: Vec<String>,
+    /// At most this many notifications per rule...
+    pub rate_limit: usize,
+    /// ...within this window
+    pub rate_window: Duration,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            sinks: vec![SinkKind::Toast],
+            webhook_url: None,
+            rules: Vec::new(),
+            rule_errors: Vec::new(),
+            rate_limit: 5,
+            rate_window: Duration::from_secs(60),
+        }
+    }
+}
+
+impl NotificationConfig {
+    /// Read `RAY_NOTIFY_SINKS`, `RAY_NOTIFY_WEBHOOK`, `RAY_ALERT_RULES` and `RAY_ALERT_RATE_LIMIT`
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(raw) = std::env::var("RAY_NOTIFY_SINKS") {
+            config.sinks = raw
+                .split(',')
+                .filter_map(|sink| match sink.trim() {
+                    "toast" => Some(SinkKind::Toast),
+                    "terminal" => Some(SinkKind::Terminal),
+                    "webhook" => Some(SinkKind::Webhook),
+                    _ => None,
+                })
+                .collect();
+        }
+
+        config.webhook_url = std::env::var("RAY_NOTIFY_WEBHOOK")
+            .ok()
+            .filter(|url| !url.is_empty());
+        // A webhook URL on its own is enough to enable the sink
+        if config.webhook_url.is_some() && !config.sinks.contains(&SinkKind::Webhook) {
+            config.sinks.push(SinkKind::Webhook);
+        }
+
+        if let Ok(raw) = std::env::var("RAY_ALERT_RULES") {
+            (config.rules, config.rule_errors) = parse_rules(&raw);
+        }
+
+        // "<count>/<seconds>", e.g. "5/60"
+        if let Some((count, seconds)) = std::env::var("RAY_ALERT_RATE_LIMIT").ok().and_then(|raw| {
+            let (count, seconds) = raw.split_once('/')?;
+            Some((count.trim().parse().ok()?, seconds.trim().parse().ok()?))
+        }) {
+            config.rate_limit = count;
+            config.rate_window = Duration::from_secs(seconds);
+        }
+
+        config
+    }
+}
+
+/// Turns stored entries into notifications and fans them out to the sinks
+pub struct NotificationCenter {
+    rules: Vec<AlertRule>,
+    rate_limit: usize,
+    rate_window: Duration,
+    limiters: Mutex<HashMap<String, RateLimiter>>,
+    sinks: Mutex<Vec<Box<dyn NotificationSink>>>,
+    toasts: Arc<ToastQueue>,
+}
+
+impl NotificationCenter {
+    /// Build the center and its sinks, returning errors for sinks that could not be created
+    pub fn from_config(config: &NotificationConfig) -> (Self, Vec<String>) {
+        let center = Self {
+            rules: config.rules.clone(),
+            rate_limit: config.rate_limit,
+            rate_window: config.rate_window,
+            limiters: Mutex::new(HashMap::new()),
+            sinks: Mutex::new(Vec::new()),
+            toasts: Arc::new(ToastQueue::new()),
+        };
+        let mut errors = config.rule_errors.clone();
+
+        for kind in &config.sinks {
+            match kind {
+                SinkKind::Toast => center.add_sink(Box::new(ToastSink::new(center.toasts.clone()))),
+                SinkKind::Terminal => center.add_sink(Box::new(TerminalSink)),
+                SinkKind::Webhook => match config.webhook_url.as_deref().map(WebhookSink::new) {
+                    Some(Ok(sink)) => center.add_sink(Box::new(sink)),
+                    Some(Err(e)) => errors.push(format!("Invalid webhook URL: {e}")),
+                    None => errors.push("Webhook sink needs RAY_NOTIFY_WEBHOOK".to_string()),
+                },
+            }
+        }
+
+        (center, errors)
+    }
+
+    pub fn add_sink(&self, sink: Box<dyn NotificationSink>) {
+        let mut sinks = self.sinks.lock().unwrap();
+        // One sink per kind, so headless mode can add the terminal sink unconditionally
+        if sinks.iter().all(|existing| existing.name() != sink.name()) {
+            sinks.push(sink);
+        }
+    }
+
+    /// Notifications the entry should produce, after rate limiting
+    pub fn notifications_for(&self, entry: &EventEntry) -> Vec<Notification> {
+        let mut notifications = Vec::new();
+
+        if entry.event_type == NOTIFY_RULE {
+            notifications.push((NOTIFY_RULE.to_string(), notify_notification(entry)));
+        }
+
+        for rule in self.rules.iter().filter(|rule| rule.matches(entry)) {
+            notifications.push((rule.name.clone(), rule_notification(rule, entry)));
+        }
+
+        let now = Instant::now();
+        let mut limiters = self.limiters.lock().unwrap();
+        notifications
+            .into_iter()
+            .filter_map(|(key, mut notification)| {
+                let suppressed = limiters
+                    .entry(key)
+                    .or_insert_with(|| RateLimiter::new(self.rate_limit, self.rate_window))
+                    .check(now)?;
+                if suppressed > 0 {
+                    notification
+                        .body
+                        .push_str(&format!(" (+{suppressed} suppressed)"));
+                }
+                Some(notification)
+            })
+            .collect()
+    }
+
+    /// Send the entry's notifications to every sink, returning the errors
+    pub fn dispatch(&self, entry: &EventEntry) -> Vec<String> {
+        let notifications = self.notifications_for(entry);
+        if notifications.is_empty() {
+            return Vec::new();
+        }
+
+        let sinks = self.sinks.lock().unwrap();
+        let mut errors = Vec::new();
+        for notification in &notifications {
+            for sink in sinks.iter() {
+                if let Err(e) = sink.send(notification) {
+                    errors.push(format!("{} sink failed: {e:#}", sink.name()));
+                }
+            }
+        }
+        errors
+    }
+
+    pub fn toasts(&self) -> &Arc<ToastQueue> {
+        &self.toasts
+    }
+}
+
+fn notify_notification(entry: &EventEntry) -> Notification {
+    let body = entry
+        .raw_payload
+        .get("content")
+        .and_then(|content| content.get("value"))
+        .map(|value| match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+        .unwrap_or_default();
+
+    Notification {
+        title: "Ray".to_string(),
+        body,
+        timestamp: timestamp_for(entry),
+        event_id: Some(entry.id),
+        rule: None,
+    }
+}
+
+fn rule_notification(rule: &AlertRule, entry: &EventEntry) -> Notification {
+    let body = if entry.description.is_empty() {
+        entry.label.clone()
+    } else {
+        format!("{}: {}", entry.label, entry.description)
+    };
+
+    Notification {
+        title: format!("Alert: {}", rule.name),
+        body,
+        timestamp: timestamp_for(entry),
+        event_id: Some(entry.id),
+        rule: Some(rule.name.clone()),
+    }
+}
+
+fn timestamp_for(entry: &EventEntry) -> String {
+    if entry.timestamp.is_empty() {
+        Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+    } else {
+        entry.timestamp.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u64, event_type: &str, payload: Value) -> EventEntry {
+        EventEntry {
+            id,
+            timestamp: "2024-01-01 10:00:00".to_string(),
+            label: "Exception".to_string(),
+            description: "boom".to_string(),
+            content_type: "custom_ui".to_string(),
+            event_type: event_type.to_string(),
+            project: None,
+            raw_payload: payload,
+        }
+    }
+
+    #[test]
+    fn test_notify_payload_always_notifies() {
+        let (center, errors) = NotificationCenter::from_config(&NotificationConfig::default());
+        assert!(errors.is_empty());
+
+        let notify = entry(1, "notify", json!({"content": {"value": "Deploy done"}}));
+        assert!(center.dispatch(&notify).is_empty());
+
+        let toasts = center.toasts().all();
+        assert_eq!(toasts.len(), 1);
+        assert_eq!(toasts[0].notification.body, "Deploy done");
+        assert_eq!(toasts[0].notification.event_id, Some(1));
+    }
+
+    #[test]
+    fn test_rules_are_rate_limited() {
+        let config = NotificationConfig {
+            rules: parse_rules("exception").0,
+            rate_limit: 2,
+            ..NotificationConfig::default()
+        };
+        let (center, _) = NotificationCenter::from_config(&config);
+
+        let sent: usize = (1..=10)
+            .map(|id| {
+                center
+                    .notifications_for(&entry(id, "exception", json!({})))
+                    .len()
+            })
+            .sum();
+        assert_eq!(sent, 2);
+        assert!(center
+            .notifications_for(&entry(11, "log", json!({})))
+            .is_empty());
+    }
+}
@@ -0,0 +1,250 @@
+use crate::events::base::resolve_field;
+use crate::events::{EventEntry, EventType};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Comparison used by a field condition
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    Eq,
+    NotEq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Contains,
+}
+
+/// `field <op> value`, where field is a dotted path into the payload
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldCondition {
+    pub path: String,
+    pub operator: Operator,
+    pub value: String,
+}
+
+impl FieldCondition {
+    fn parse(raw: &str) -> Option<Self> {
+        // Longest operators first so ">=" is not read as ">"
+        const OPERATORS: &[(&str, Operator)] = &[
+            (">=", Operator::Gte),
+            ("<=", Operator::Lte),
+            ("!=", Operator::NotEq),
+            ("=", Operator::Eq),
+            (">", Operator::Gt),
+            ("<", Operator::Lt),
+            ("~", Operator::Contains),
+        ];
+
+        let (position, symbol, operator) = OPERATORS
+            .iter()
+            .filter_map(|(symbol, operator)| {
+                raw.find(symbol)
+                    .map(|position| (position, *symbol, *operator))
+            })
+            .min_by_key(|(position, symbol, _)| (*position, std::cmp::Reverse(symbol.len())))?;
+
+        let path = raw[..position].trim();
+        let value = raw[position + symbol.len()..].trim();
+        if path.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            path: path.to_string(),
+            operator,
+            value: value.to_string(),
+        })
+    }
+
+    fn matches(&self, payload: &Value) -> bool {
+        let Some(actual) = resolve_field(payload, &self.path) else {
+            return self.operator == Operator::NotEq;
+        };
+        let actual_text = match actual {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+
+        match self.operator {
+            Operator::Eq => actual_text.eq_ignore_ascii_case(&self.value),
+            Operator::NotEq => !actual_text.eq_ignore_ascii_case(&self.value),
+            Operator::Contains => actual_text
+                .to_lowercase()
+                .contains(&self.value.to_lowercase()),
+            Operator::Gt | Operator::Gte | Operator::Lt | Operator::Lte => {
+                let (Some(actual), Ok(expected)) = (as_number(actual), self.value.parse::<f64>())
+                else {
+                    return false;
+                };
+                match self.operator {
+                    Operator::Gt => actual > expected,
+                    Operator::Gte => actual >= expected,
+                    Operator::Lt => actual < expected,
+                    _ => actual <= expected,
+                }
+            }
+        }
+    }
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// An alert rule: an event type plus optional field conditions, all of which must match
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlertRule {
+    /// Original rule text, used as the rule's name
+    pub name: String,
+    pub event_type: EventType,
+    pub conditions: Vec<FieldCondition>,
+}
+
+impl AlertRule {
+    /// Parse `<type> [field<op>value ...]`, e.g. `request Status>=500`
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let mut parts = raw.split_whitespace();
+        let event_type = parts
+            .next()
+            .ok_or_else(|| "Empty alert rule".to_string())?
+            .parse::<EventType>()?;
+
+        let conditions = parts
+            .map(|part| {
+                FieldCondition::parse(part).ok_or_else(|| format!("Invalid condition: {part}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            name: raw.trim().to_string(),
+            event_type,
+            conditions,
+        })
+    }
+
+    pub fn matches(&self, entry: &EventEntry) -> bool {
+        entry.event_type.parse::<EventType>().ok() == Some(self.event_type)
+            && self
+                .conditions
+                .iter()
+                .all(|condition| condition.matches(&entry.raw_payload))
+    }
+}
+
+/// Parse `;`-separated rules, returning the valid ones and the errors
+pub fn parse_rules(raw: &str) -> (Vec<AlertRule>, Vec<String>) {
+    let mut rules = Vec::new();
+    let mut errors = Vec::new();
+
+    for rule in raw.split(';').map(str::trim).filter(|r| !r.is_empty()) {
+        match AlertRule::parse(rule) {
+            Ok(rule) => rules.push(rule),
+            Err(e) => errors.push(format!("{rule}: {e}")),
+        }
+    }
+
+    (rules, errors)
+}
+
+/// Sliding-window limiter: at most `max` notifications per `window`
+#[derive(Debug)]
+pub struct RateLimiter {
+    max: usize,
+    window: Duration,
+    sent: VecDeque<Instant>,
+    suppressed: usize,
+}
+
+impl RateLimiter {
+    pub fn new(max: usize, window: Duration) -> Self {
+        Self {
+            max,
+            window,
+            sent: VecDeque::new(),
+            suppressed: 0,
+        }
+    }
+
+    /// `Some(n)` if a notification may be sent now, where `n` is how many were
+    /// suppressed since the last one went out; `None` if it must be dropped
+    pub fn check(&mut self, now: Instant) -> Option<usize> {
+        while let Some(oldest) = self.sent.front() {
+            if now.duration_since(*oldest) >= self.window {
+                self.sent.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        if self.sent.len() >= self.max {
+            self.suppressed += 1;
+            return None;
+        }
+
+        self.sent.push_back(now);
+        Some(std::mem::take(&mut self.suppressed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(event_type: &str, payload: Value) -> EventEntry {
+        EventEntry {
+            id: 1,
+            timestamp: String::new(),
+            label: String::new(),
+            description: String::new(),
+            content_type: "custom_ui".to_string(),
+            event_type: event_type.to_string(),
+            project: None,
+            raw_payload: payload,
+        }
+    }
+
+    #[test]
+    fn test_rule_matches_type_and_fields() {
+        let rule = AlertRule::parse("request Status>=500").unwrap();
+        let failed = entry("request", json!({"content": {"values": {"Status": 503}}}));
+        let ok = entry("request", json!({"content": {"values": {"Status": 200}}}));
+
+        assert!(rule.matches(&failed));
+        assert!(!rule.matches(&ok));
+        assert!(!rule.matches(&entry("exception", json!({}))));
+    }
+
+    #[test]
+    fn test_parse_rules_reports_errors() {
+        let (rules, errors) = parse_rules("exception; bogus; log values~payment");
+        assert_eq!(rules.len(), 2);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            rules[1].conditions[0],
+            FieldCondition {
+                path: "values".to_string(),
+                operator: Operator::Contains,
+                value: "payment".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_rate_limiter_counts_suppressed() {
+        let mut limiter = RateLimiter::new(2, Duration::from_secs(60));
+        let start = Instant::now();
+
+        assert_eq!(limiter.check(start), Some(0));
+        assert_eq!(limiter.check(start), Some(0));
+        assert_eq!(limiter.check(start), None);
+        assert_eq!(limiter.check(start), None);
+        assert_eq!(limiter.check(start + Duration::from_secs(61)), Some(2));
+    }
+}
@@ -0,0 +1,149 @@
+use super::{Notification, NotificationSink};
+use anyhow::{anyhow, Result};
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{Method, Request};
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+/// Toasts kept around before the oldest are dropped
+const MAX_TOASTS: usize = 5;
+
+/// A notification waiting to be shown in the GUI
+#[derive(Clone, Debug)]
+pub struct Toast {
+    pub id: u64,
+    pub notification: Notification,
+}
+
+/// Toasts shared between the toast sink and the GUI
+#[derive(Default)]
+pub struct ToastQueue {
+    toasts: Mutex<VecDeque<Toast>>,
+    next_id: Mutex<u64>,
+}
+
+impl ToastQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&self, notification: Notification) {
+        let id = {
+            let mut next_id = self.next_id.lock().unwrap();
+            *next_id += 1;
+            *next_id
+        };
+
+        let mut toasts = self.toasts.lock().unwrap();
+        toasts.push_back(Toast { id, notification });
+        while toasts.len() > MAX_TOASTS {
+            toasts.pop_front();
+        }
+    }
+
+    /// Pending toasts, oldest first
+    pub fn all(&self) -> Vec<Toast> {
+        self.toasts.lock().unwrap().iter().cloned().collect()
+    }
+
+    pub fn dismiss(&self, id: u64) {
+        self.toasts.lock().unwrap().retain(|toast| toast.id != id);
+    }
+}
+
+/// In-app toast shown in the GPUI window
+pub struct ToastSink {
+    queue: Arc<ToastQueue>,
+}
+
+impl ToastSink {
+    pub fn new(queue: Arc<ToastQueue>) -> Self {
+        Self { queue }
+    }
+}
+
+impl NotificationSink for ToastSink {
+    fn name(&self) -> &'static str {
+        "toast"
+    }
+
+    fn send(&self, notification: &Notification) -> Result<()> {
+        self.queue.push(notification.clone());
+        Ok(())
+    }
+}
+
+/// Terminal bell plus a single line on stderr, for headless use
+pub struct TerminalSink;
+
+impl NotificationSink for TerminalSink {
+    fn name(&self) -> &'static str {
+        "terminal"
+    }
+
+    fn send(&self, notification: &Notification) -> Result<()> {
+        let mut stderr = io::stderr();
+        writeln!(
+            stderr,
+            "\x07[{}] {}: {}",
+            notification.timestamp, notification.title, notification.body
+        )?;
+        stderr.flush()?;
+        Ok(())
+    }
+}
+
+/// POSTs each notification as JSON to a local URL
+pub struct WebhookSink {
+    url: hyper::Uri,
+}
+
+impl WebhookSink {
+    pub fn new(url: &str) -> Result<Self> {
+        let url: hyper::Uri = url.parse()?;
+        if url.scheme_str() != Some("http") {
+            return Err(anyhow!("Only http:// webhook URLs are supported: {url}"));
+        }
+        Ok(Self { url })
+    }
+}
+
+impl NotificationSink for WebhookSink {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn send(&self, notification: &Notification) -> Result<()> {
+        // Ingestion must not wait on the webhook, so the request runs in the background
+        let handle = tokio::runtime::Handle::try_current()
+            .map_err(|_| anyhow!("Webhook sink needs a running tokio runtime"))?;
+
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(self.url.clone())
+            .header("content-type", "application/json")
+            .body(Full::new(Bytes::from(notification.to_json().to_string())))?;
+
+        let url = self.url.clone();
+        handle.spawn(async move {
+            let client = Client::builder(TokioExecutor::new()).build_http();
+            match client.request(request).await {
+                Ok(response) if !response.status().is_success() => {
+                    log::warn!(
+                        target: "ray::Webhook",
+                        "Webhook {url} responded with {}",
+                        response.status()
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!(target: "ray::Webhook", "Webhook {url} failed: {e}"),
+            }
+        });
+
+        Ok(())
+    }
+}
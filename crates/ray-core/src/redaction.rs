@@ -0,0 +1,261 @@
+// redaction.rs - masks secrets and personal data in payloads before they are stored
+use crate::config::env_flag;
+use regex::Regex;
+use serde_json::Value;
+
+/// What masked values are replaced with; front-ends look for it to mark redacted fields
+pub const REDACTED: &str = "[redacted]";
+
+/// Header names masked when `RAY_REDACT_HEADERS` is not set
+const DEFAULT_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+    "x-auth-token",
+    "x-csrf-token",
+    "x-xsrf-token",
+];
+
+/// JSON key patterns masked when `RAY_REDACT_KEYS` is not set
+const DEFAULT_KEYS: &[&str] = &[
+    "*password*",
+    "*passwd*",
+    "*secret*",
+    "*token*",
+    "api_key",
+    "apikey",
+    "credit_card*",
+    "card_number",
+    "cvv",
+];
+
+/// Whether a displayed value was (partly) masked at ingestion
+pub fn is_redacted(text: &str) -> bool {
+    text.contains(REDACTED)
+}
+
+/// Rules for masking sensitive values at ingestion
+#[derive(Clone, Debug)]
+pub struct RedactionConfig {
+    pub enabled: bool,
+    /// Lowercase names of headers whose values are masked
+    pub headers: Vec<String>,
+    /// Lowercase key patterns, `*` matching any run of characters
+    pub keys: Vec<String>,
+    /// Matches inside any string value are replaced
+    pub patterns: Vec<Regex>,
+    /// Mask every query binding, since positional values can't be matched by key
+    pub bindings: bool,
+    /// Patterns that failed to compile, logged at startup
+    pub errors: Vec<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            headers: DEFAULT_HEADERS.iter().map(|h| h.to_string()).collect(),
+            keys: DEFAULT_KEYS.iter().map(|k| k.to_string()).collect(),
+            patterns: Vec::new(),
+            bindings: false,
+            errors: Vec::new(),
+        }
+    }
+}
+
+impl RedactionConfig {
+    /// Read `RAY_REDACT`, `RAY_REDACT_HEADERS`, `RAY_REDACT_KEYS` (comma-separated),
+    /// `RAY_REDACT_PATTERNS` (one regex per line) and `RAY_REDACT_BINDINGS`
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        let list = |raw: String| -> Vec<String> {
+            raw.split(',')
+                .map(|item| item.trim().to_lowercase())
+                .filter(|item| !item.is_empty())
+                .collect()
+        };
+
+        config.enabled = env_flag("RAY_REDACT").unwrap_or(true);
+        if let Ok(raw) = std::env::var("RAY_REDACT_HEADERS") {
+            config.headers = list(raw);
+        }
+        if let Ok(raw) = std::env::var("RAY_REDACT_KEYS") {
+            config.keys = list(raw);
+        }
+        if let Ok(raw) = std::env::var("RAY_REDACT_PATTERNS") {
+            for pattern in raw.lines().map(str::trim).filter(|p| !p.is_empty()) {
+                match Regex::new(pattern) {
+                    Ok(regex) => config.patterns.push(regex),
+                    Err(e) => config
+                        .errors
+                        .push(format!("Invalid redaction pattern {pattern}: {e}")),
+                }
+            }
+        }
+        config.bindings = env_flag("RAY_REDACT_BINDINGS").unwrap_or(false);
+
+        config
+    }
+
+    /// Mask sensitive values in place, returning how many were masked
+    ///
+    /// Everything but the payload `type` and `origin` is covered, so the typed
+    /// events, exports and copies built from the stored payload never see them.
+    pub fn redact(&self, payload: &mut Value) -> usize {
+        if !self.enabled {
+            return 0;
+        }
+        let Some(object) = payload.as_object_mut() else {
+            return 0;
+        };
+
+        let mut count = 0;
+        for (key, value) in object.iter_mut() {
+            if key == "type" || key == "origin" {
+                continue;
+            }
+            count += self.redact_value(value, false);
+        }
+
+        if self.bindings {
+            if let Some(bindings) = payload
+                .pointer_mut("/content/bindings")
+                .and_then(Value::as_array_mut)
+            {
+                for binding in bindings {
+                    count += mask(binding);
+                }
+            }
+        }
+        count
+    }
+
+    /// Apply the regex patterns to text that isn't part of the payload, like script labels
+    pub fn redact_text(&self, text: &str) -> String {
+        if !self.enabled {
+            return text.to_string();
+        }
+        self.patterns
+            .iter()
+            .fold(text.to_string(), |text, pattern| {
+                pattern.replace_all(&text, REDACTED).into_owned()
+            })
+    }
+
+    fn redact_value(&self, value: &mut Value, in_headers: bool) -> usize {
+        match value {
+            Value::Object(map) => map
+                .iter_mut()
+                .map(|(key, value)| {
+                    let key = key.to_lowercase();
+                    if (in_headers && self.headers.contains(&key)) || self.is_sensitive_key(&key) {
+                        mask(value)
+                    } else {
+                        self.redact_value(value, key == "headers")
+                    }
+                })
+                .sum(),
+            Value::Array(items) => items
+                .iter_mut()
+                .map(|item| self.redact_value(item, false))
+                .sum(),
+            Value::String(text) => {
+                let count = self
+                    .patterns
+                    .iter()
+                    .map(|pattern| pattern.find_iter(text).count())
+                    .sum();
+                if count > 0 {
+                    *text = self.redact_text(text);
+                }
+                count
+            }
+            _ => 0,
+        }
+    }
+
+    fn is_sensitive_key(&self, key: &str) -> bool {
+        self.keys
+            .iter()
+            .any(|pattern| matches_wildcard(pattern, key))
+    }
+}
+
+/// Replace a value with the marker; nulls stay as they are since there is nothing to hide
+fn mask(value: &mut Value) -> usize {
+    match value {
+        Value::Null => 0,
+        Value::String(text) if text == REDACTED => 0,
+        _ => {
+            *value = Value::String(REDACTED.to_string());
+            1
+        }
+    }
+}
+
+/// Match `text` against a pattern where `*` stands for any run of characters
+fn matches_wildcard(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let [first, middle @ .., last] = parts.as_slice() else {
+        return pattern == text;
+    };
+    if text.len() < first.len() + last.len() || !text.starts_with(first) || !text.ends_with(last) {
+        return false;
+    }
+
+    let mut rest = &text[first.len()..text.len() - last.len()];
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_headers_keys_and_patterns() {
+        let config = RedactionConfig {
+            patterns: vec![Regex::new(r"[\w.]+@[\w.]+").unwrap()],
+            bindings: true,
+            ..RedactionConfig::default()
+        };
+
+        let mut payload = json!({
+            "type": "request",
+            "content": {"values": {
+                "URL": "https://example.com/login?email=jane@example.com",
+                "Headers": {"Authorization": ["Bearer abc"], "Accept": "text/html", "X-Empty": null},
+                "Data": {"user": {"email": "jane", "password": "hunter2", "reset_token": null}}
+            }}
+        });
+        assert_eq!(config.redact(&mut payload), 3);
+        assert_eq!(
+            payload["content"]["values"],
+            json!({
+                "URL": "https://example.com/login?email=[redacted]",
+                "Headers": {"Authorization": "[redacted]", "Accept": "text/html", "X-Empty": null},
+                "Data": {"user": {"email": "jane", "password": "[redacted]", "reset_token": null}}
+            })
+        );
+        // Already masked values are left alone
+        assert_eq!(config.redact(&mut payload), 0);
+
+        let mut query =
+            json!({"type": "query", "content": {"sql": "select ?", "bindings": [42, "x"]}});
+        assert_eq!(config.redact(&mut query), 2);
+        assert_eq!(query["content"]["bindings"], json!([REDACTED, REDACTED]));
+
+        assert!(matches_wildcard("*token*", "csrf_token"));
+        assert!(matches_wildcard("credit_card*", "credit_card_number"));
+        assert!(!matches_wildcard("api_key", "api_keys"));
+        assert!(!matches_wildcard("a*a", "a"));
+    }
+}
@@ -0,0 +1,297 @@
+// scripts.rs - user Rhai scripts that run on each payload before it is processed
+use rhai::{Dynamic, Engine, Scope, AST};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often the scripts directory is checked for changes
+const RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Upper bound on the work a single script run may do, so a stray loop can't hang ingestion
+const MAX_OPERATIONS: u64 = 100_000;
+
+/// Where payload scripts are loaded from
+#[derive(Clone, Debug, Default)]
+pub struct ScriptConfig {
+    pub dir: Option<PathBuf>,
+}
+
+impl ScriptConfig {
+    /// Read `RAY_SCRIPTS_DIR`; every `*.rhai` file in it runs, in file name order
+    pub fn from_env() -> Self {
+        Self {
+            dir: std::env::var("RAY_SCRIPTS_DIR")
+                .ok()
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from),
+        }
+    }
+}
+
+/// A payload after all scripts ran over it
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptedPayload {
+    pub payload: Value,
+    /// Set by scripts through the `label` and `description` variables, replacing
+    /// what the processor derives
+    pub label: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScriptOutcome {
+    Keep(ScriptedPayload),
+    /// A script returned `false`
+    Drop {
+        script: String,
+    },
+}
+
+struct Script {
+    name: String,
+    ast: AST,
+}
+
+#[derive(Default)]
+struct LoadedScripts {
+    scripts: Vec<Arc<Script>>,
+    /// File names, sizes and modification times the scripts were compiled from
+    fingerprint: Vec<(PathBuf, u64, Option<SystemTime>)>,
+    last_check: Option<Instant>,
+}
+
+/// Runs the scripts from the configured directory, reloading them when files change
+///
+/// Each script sees the payload as a `payload` map it may modify (changing
+/// `payload.type` re-routes the event), can set `label` and `description`, and
+/// drops the event by returning `false`.
+pub struct ScriptRunner {
+    dir: Option<PathBuf>,
+    engine: Engine,
+    loaded: Mutex<LoadedScripts>,
+}
+
+impl ScriptRunner {
+    pub fn new(config: &ScriptConfig) -> Self {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+
+        Self {
+            dir: config.dir.clone(),
+            engine,
+            loaded: Mutex::new(LoadedScripts::default()),
+        }
+    }
+
+    /// Run every script over the payload; errors name the script and leave the
+    /// payload as it was before that script
+    pub fn run(&self, payload: &Value) -> (ScriptOutcome, Vec<String>) {
+        let mut errors = self.reload_if_changed();
+        let scripts = self.loaded.lock().unwrap().scripts.clone();
+
+        let mut scripted = ScriptedPayload {
+            payload: payload.clone(),
+            label: None,
+            description: None,
+        };
+        for script in scripts {
+            match self.run_script(&script, &scripted) {
+                Ok(Some(next)) => scripted = next,
+                Ok(None) => {
+                    return (
+                        ScriptOutcome::Drop {
+                            script: script.name.clone(),
+                        },
+                        errors,
+                    )
+                }
+                Err(e) => errors.push(format!("{}: {e}", script.name)),
+            }
+        }
+
+        (ScriptOutcome::Keep(scripted), errors)
+    }
+
+    fn run_script(
+        &self,
+        script: &Script,
+        input: &ScriptedPayload,
+    ) -> Result<Option<ScriptedPayload>, String> {
+        let optional =
+            |value: &Option<String>| value.clone().map(Dynamic::from).unwrap_or(Dynamic::UNIT);
+
+        let mut scope = Scope::new();
+        scope.push(
+            "payload",
+            rhai::serde::to_dynamic(&input.payload).map_err(|e| e.to_string())?,
+        );
+        scope.push("label", optional(&input.label));
+        scope.push("description", optional(&input.description));
+
+        let result: Dynamic = self
+            .engine
+            .eval_ast_with_scope(&mut scope, &script.ast)
+            .map_err(|e| e.to_string())?;
+        if result.as_bool() == Ok(false) {
+            return Ok(None);
+        }
+
+        let payload: Value = scope
+            .get_value::<Dynamic>("payload")
+            .map(|payload| rhai::serde::from_dynamic(&payload))
+            .transpose()
+            .map_err(|e| e.to_string())?
+            .ok_or("payload was removed from the scope")?;
+        if !payload.is_object() {
+            return Err("payload must stay an object map".to_string());
+        }
+
+        let text = |name: &str| {
+            scope
+                .get_value::<Dynamic>(name)
+                .filter(|value| !value.is_unit())
+                .map(|value| value.to_string())
+        };
+        Ok(Some(ScriptedPayload {
+            payload,
+            label: text("label"),
+            description: text("description"),
+        }))
+    }
+
+    /// Recompile the scripts if the directory listing changed, at most once per interval
+    fn reload_if_changed(&self) -> Vec<String> {
+        let Some(dir) = &self.dir else {
+            return Vec::new();
+        };
+
+        let mut loaded = self.loaded.lock().unwrap();
+        if loaded
+            .last_check
+            .is_some_and(|checked| checked.elapsed() < RELOAD_INTERVAL)
+        {
+            return Vec::new();
+        }
+        loaded.last_check = Some(Instant::now());
+
+        let fingerprint = match script_files(dir) {
+            Ok(fingerprint) => fingerprint,
+            Err(e) => {
+                // Report an unreadable directory once, not on every payload
+                let unreadable = vec![(dir.clone(), 0, None)];
+                if loaded.fingerprint == unreadable {
+                    return Vec::new();
+                }
+                loaded.fingerprint = unreadable;
+                loaded.scripts.clear();
+                return vec![format!("Could not read {}: {e}", dir.display())];
+            }
+        };
+        if fingerprint == loaded.fingerprint {
+            return Vec::new();
+        }
+
+        let mut errors = Vec::new();
+        loaded.scripts = fingerprint
+            .iter()
+            .filter_map(|(path, _, _)| {
+                let name = path.file_name()?.to_string_lossy().to_string();
+                match std::fs::read_to_string(path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|source| self.engine.compile(source).map_err(|e| e.to_string()))
+                {
+                    Ok(ast) => Some(Arc::new(Script { name, ast })),
+                    Err(e) => {
+                        errors.push(format!("{name}: {e}"));
+                        None
+                    }
+                }
+            })
+            .collect();
+        loaded.fingerprint = fingerprint;
+        errors
+    }
+}
+
+/// `*.rhai` files in the directory, sorted by name, with their size and modification time
+fn script_files(dir: &Path) -> std::io::Result<Vec<(PathBuf, u64, Option<SystemTime>)>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "rhai")
+        {
+            let metadata = entry.metadata()?;
+            files.push((path, metadata.len(), metadata.modified().ok()));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_scripts_transform_and_drop() {
+        let dir = std::env::temp_dir().join(format!("ray-scripts-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("10-redact.rhai"),
+            r#"
+                if payload.content.password != () { payload.content.password = "***"; }
+                if payload.content.queue != () {
+                    payload.type = "log";
+                    label = "Queue " + payload.content.queue;
+                }
+            "#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("20-drop.rhai"),
+            r#"payload.content.path != "/health""#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("30-broken.rhai"), "let = ;").unwrap();
+
+        let runner = ScriptRunner::new(&ScriptConfig {
+            dir: Some(dir.clone()),
+        });
+
+        let (outcome, errors) = runner.run(&json!({
+            "type": "request",
+            "content": {"password": "secret", "queue": "emails", "path": "/login"}
+        }));
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("30-broken.rhai"));
+        assert_eq!(
+            outcome,
+            ScriptOutcome::Keep(ScriptedPayload {
+                payload: json!({
+                    "type": "log",
+                    "content": {"password": "***", "queue": "emails", "path": "/login"}
+                }),
+                label: Some("Queue emails".to_string()),
+                description: None,
+            })
+        );
+
+        // Compile errors are reported once, when the scripts are (re)loaded
+        let (outcome, errors) =
+            runner.run(&json!({"type": "request", "content": {"path": "/health"}}));
+        assert!(errors.is_empty());
+        assert_eq!(
+            outcome,
+            ScriptOutcome::Drop {
+                script: "20-drop.rhai".to_string()
+            }
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,517 @@
+// server/access.rs - listener settings and who may send events to the server
+use super::forward::ForwardConfig;
+use super::tls::TlsConfig;
+use crate::config::env_flag;
+use hyper::header::{HeaderMap, AUTHORIZATION};
+use std::collections::HashSet;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+/// Port Ray clients send to unless configured otherwise
+pub const DEFAULT_PORT: u16 = 23517;
+
+/// Largest request body accepted when `RAY_MAX_BODY_BYTES` is not set
+const DEFAULT_MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Largest body a compressed one may expand to when `RAY_MAX_DECODED_BYTES` is not set
+const DEFAULT_MAX_DECODED_BYTES: usize = 64 * 1024 * 1024;
+
+/// Payloads waiting to be stored before further ones are dropped
+const DEFAULT_QUEUE_CAPACITY: usize = 10_000;
+
+/// Payloads a worker stores at once
+const DEFAULT_BATCH_SIZE: usize = 100;
+
+/// Upper bound for the default worker count; storing is serialized, only processing scales
+const MAX_DEFAULT_WORKERS: usize = 4;
+
+/// Open connections accepted when `RAY_MAX_CONNECTIONS` is not set
+const DEFAULT_MAX_CONNECTIONS: usize = 1024;
+
+/// Time a client gets to send request headers or finish the TLS handshake
+const DEFAULT_HEADER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long shutdown waits for in-flight requests
+const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Header a token can be sent in, for clients that can't set `Authorization`
+const TOKEN_HEADER: &str = "x-ray-token";
+
+/// Colors for projects that don't pick their own, handed out in listener order
+const PROJECT_COLORS: [u32; 6] = [0x3b82f6, 0x22c55e, 0xf59e0b, 0xa855f7, 0xec4899, 0x14b8a6];
+
+/// Name and color that events from a listener are tagged with
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Project {
+    pub name: String,
+    /// `0xRRGGBB`
+    pub color: u32,
+}
+
+/// An address the server accepts connections on
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Listener {
+    pub addr: SocketAddr,
+    /// Project events received here are tagged with; untagged when `None`
+    pub project: Option<Project>,
+}
+
+impl Listener {
+    /// `name=host:port` with an optional `#rrggbb` color, e.g. `shop=127.0.0.1:23518#f59e0b`
+    ///
+    /// Without a color the listener gets the `index`th one from a fixed palette.
+    pub fn parse(raw: &str, index: usize) -> Result<Self, String> {
+        let (name, rest) = raw
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid listener, expected name=host:port: {raw}"))?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(format!("Listener without a project name: {raw}"));
+        }
+        let (addr, color) = match rest.split_once('#') {
+            Some((addr, color)) => (addr, Some(color.trim())),
+            None => (rest, None),
+        };
+        let addr =
+            parse_bind(addr.trim()).ok_or_else(|| format!("Invalid listener address: {raw}"))?;
+        let color = match color {
+            Some(color) => u32::from_str_radix(color, 16)
+                .ok()
+                .filter(|_| color.len() == 6)
+                .ok_or_else(|| format!("Invalid listener color, expected #rrggbb: {raw}"))?,
+            None => PROJECT_COLORS[index % PROJECT_COLORS.len()],
+        };
+        Ok(Self {
+            addr,
+            project: Some(Project {
+                name: name.to_string(),
+                color,
+            }),
+        })
+    }
+}
+
+/// An IP address or CIDR block from the allowlist
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IpNetwork {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpNetwork {
+    /// `10.0.0.0/8`, `fd00::/8` or a single address
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let (addr, prefix) = match raw.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (raw, None),
+        };
+        let addr: IpAddr = addr
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid address in allowlist: {raw}"))?;
+        let max_prefix = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .trim()
+                .parse()
+                .ok()
+                .filter(|prefix| *prefix <= max_prefix)
+                .ok_or_else(|| format!("Invalid prefix length in allowlist: {raw}"))?,
+            None => max_prefix,
+        };
+        Ok(Self { addr, prefix })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        // IPv4 clients on a dual-stack socket show up as ::ffff:a.b.c.d
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                prefix_matches(&network.octets(), &ip.octets(), self.prefix)
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                prefix_matches(&network.octets(), &ip.octets(), self.prefix)
+            }
+            _ => false,
+        }
+    }
+}
+
+fn prefix_matches(network: &[u8], ip: &[u8], prefix: u8) -> bool {
+    let full_bytes = usize::from(prefix / 8);
+    if network[..full_bytes] != ip[..full_bytes] {
+        return false;
+    }
+    let remaining_bits = prefix % 8;
+    if remaining_bits == 0 {
+        return true;
+    }
+    let mask = 0xff_u8 << (8 - remaining_bits);
+    network[full_bytes] & mask == ip[full_bytes] & mask
+}
+
+/// Listener, access and ingestion settings for the HTTP server
+#[derive(Clone, Debug)]
+pub struct ServerConfig {
+    /// Addresses to accept connections on, each optionally tagging events with a project
+    pub listeners: Vec<Listener>,
+    /// Shared secret required on every route except the web UI files and the availability check
+    pub token: Option<String>,
+    /// Clients allowed to connect at all; empty allows everyone
+    pub allowlist: Vec<IpNetwork>,
+    /// Bodies above this are rejected before they are buffered
+    pub max_body_bytes: usize,
+    /// Compressed bodies are rejected once they decompress past this
+    pub max_decoded_bytes: usize,
+    /// Serve HTTPS instead of plain HTTP
+    pub tls: Option<TlsConfig>,
+    /// Accept HTTP/2 (h2c, or negotiated over TLS) next to HTTP/1.1
+    pub http2: bool,
+    /// Keep HTTP/1.1 connections open between requests, and ping idle HTTP/2 ones
+    pub keep_alive: bool,
+    /// Further connections are refused while this many are open
+    pub max_connections: usize,
+    pub header_timeout: Duration,
+    pub shutdown_timeout: Duration,
+    /// Payloads accepted but not yet stored; more are dropped while the queue is full
+    pub queue_capacity: usize,
+    /// Threads processing queued payloads
+    pub workers: usize,
+    pub batch_size: usize,
+    /// Other receivers that get a copy of every request
+    pub forward: Option<ForwardConfig>,
+    /// Settings that could not be parsed, logged at startup
+    pub errors: Vec<String>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            listeners: vec![Listener {
+                addr: SocketAddr::from((Ipv4Addr::LOCALHOST, DEFAULT_PORT)),
+                project: None,
+            }],
+            token: None,
+            allowlist: Vec::new(),
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            max_decoded_bytes: DEFAULT_MAX_DECODED_BYTES,
+            tls: None,
+            http2: true,
+            keep_alive: true,
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            header_timeout: DEFAULT_HEADER_TIMEOUT,
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+            queue_capacity: DEFAULT_QUEUE_CAPACITY,
+            workers: std::thread::available_parallelism()
+                .map_or(1, |count| count.get().min(MAX_DEFAULT_WORKERS)),
+            batch_size: DEFAULT_BATCH_SIZE,
+            forward: None,
+            errors: Vec::new(),
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Read `RAY_BIND` (`host:port` or just a host) or `RAY_LISTENERS` (comma-separated
+    /// `name=host:port#rrggbb` entries, which win when both are set), `RAY_TOKEN`, `RAY_ALLOW` (comma-separated
+    /// addresses and CIDR blocks), the limits and timeouts, `RAY_HTTP2`,
+    /// `RAY_KEEP_ALIVE`, the ingestion queue and the forwarding settings
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+
+        if let Ok(raw) = std::env::var("RAY_BIND") {
+            match parse_bind(raw.trim()) {
+                Some(addr) => config.listeners[0].addr = addr,
+                None => config
+                    .errors
+                    .push(format!("Invalid RAY_BIND address: {raw}")),
+            }
+        }
+
+        if let Ok(raw) = std::env::var("RAY_LISTENERS") {
+            let mut listeners: Vec<Listener> = Vec::new();
+            let entries = raw.split(',').map(str::trim).filter(|e| !e.is_empty());
+            for (index, entry) in entries.enumerate() {
+                if let Err(e) = add_listener(&mut listeners, entry, index) {
+                    config.errors.push(e);
+                }
+            }
+            // Keep the default listener rather than starting a server nobody can reach
+            if !listeners.is_empty() {
+                if std::env::var_os("RAY_BIND").is_some() {
+                    config
+                        .errors
+                        .push("RAY_BIND is ignored because RAY_LISTENERS is set".to_string());
+                }
+                config.listeners = listeners;
+            }
+        }
+
+        config.token = std::env::var("RAY_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty());
+
+        if let Ok(raw) = std::env::var("RAY_ALLOW") {
+            for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                match IpNetwork::parse(entry) {
+                    Ok(network) => config.allowlist.push(network),
+                    Err(e) => config.errors.push(e),
+                }
+            }
+            // A typo must not silently open the server to everyone
+            if config.allowlist.is_empty() {
+                config.allowlist = ["127.0.0.1", "::1"]
+                    .into_iter()
+                    .filter_map(|ip| IpNetwork::parse(ip).ok())
+                    .collect();
+            }
+        }
+
+        // Positive whole numbers; anything else keeps the default and is reported
+        let mut errors = Vec::new();
+        let mut number = |name: &str| -> Option<u64> {
+            let raw = std::env::var(name).ok()?;
+            match raw.trim().parse() {
+                Ok(value) if value > 0 => Some(value),
+                _ => {
+                    errors.push(format!("Invalid {name}: {raw}"));
+                    None
+                }
+            }
+        };
+        if let Some(bytes) = number("RAY_MAX_BODY_BYTES") {
+            config.max_body_bytes = bytes as usize;
+        }
+        if let Some(bytes) = number("RAY_MAX_DECODED_BYTES") {
+            config.max_decoded_bytes = bytes as usize;
+        }
+        if let Some(count) = number("RAY_MAX_CONNECTIONS") {
+            config.max_connections = count as usize;
+        }
+        if let Some(secs) = number("RAY_HEADER_TIMEOUT_SECS") {
+            config.header_timeout = Duration::from_secs(secs);
+        }
+        if let Some(secs) = number("RAY_SHUTDOWN_TIMEOUT_SECS") {
+            config.shutdown_timeout = Duration::from_secs(secs);
+        }
+        if let Some(capacity) = number("RAY_QUEUE_CAPACITY") {
+            config.queue_capacity = capacity as usize;
+        }
+        if let Some(count) = number("RAY_INGEST_WORKERS") {
+            config.workers = count as usize;
+        }
+        if let Some(size) = number("RAY_BATCH_SIZE") {
+            config.batch_size = size as usize;
+        }
+        config.errors.append(&mut errors);
+
+        config.http2 = env_flag("RAY_HTTP2").unwrap_or(true);
+        config.keep_alive = env_flag("RAY_KEEP_ALIVE").unwrap_or(true);
+
+        config.tls = TlsConfig::from_env();
+        if config.tls.is_none()
+            && (std::env::var_os("RAY_TLS_CERT").is_some()
+                || std::env::var_os("RAY_TLS_KEY").is_some())
+        {
+            config
+                .errors
+                .push("RAY_TLS_CERT and RAY_TLS_KEY must be set together".to_string());
+        }
+
+        let (forward, mut forward_errors) = ForwardConfig::from_env();
+        config.forward = forward;
+        config.errors.append(&mut forward_errors);
+
+        config
+    }
+
+    /// Projects of the tagged listeners, in listener order
+    /// Each project once, in listener order, even when several listeners share it
+    pub fn projects(&self) -> impl Iterator<Item = &Project> {
+        let mut seen = HashSet::new();
+        self.listeners
+            .iter()
+            .filter_map(|l| l.project.as_ref())
+            .filter(move |project| seen.insert(project.name.as_str()))
+    }
+
+    pub fn project(&self, name: &str) -> Option<&Project> {
+        self.projects().find(|project| project.name == name)
+    }
+
+    pub fn is_allowed(&self, ip: IpAddr) -> bool {
+        self.allowlist.is_empty() || self.allowlist.iter().any(|network| network.contains(ip))
+    }
+
+    /// Check the token from `Authorization: Bearer`, `X-Ray-Token` or `?token=`
+    pub fn check_token(
+        &self,
+        headers: &HeaderMap,
+        query: Option<&str>,
+    ) -> Result<(), &'static str> {
+        let Some(expected) = &self.token else {
+            return Ok(());
+        };
+
+        let from_header = headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .or_else(|| {
+                headers
+                    .get(TOKEN_HEADER)
+                    .and_then(|value| value.to_str().ok())
+            })
+            .map(str::to_string);
+        let provided = from_header.or_else(|| {
+            form_urlencoded::parse(query.unwrap_or("").as_bytes())
+                .find(|(key, _)| key == "token")
+                .map(|(_, value)| value.into_owned())
+        });
+
+        match provided {
+            None => Err("missing token"),
+            Some(token) if constant_time_eq(token.trim().as_bytes(), expected.as_bytes()) => Ok(()),
+            Some(_) => Err("invalid token"),
+        }
+    }
+}
+
+/// Parse a `RAY_LISTENERS` entry and add it unless its address is taken
+///
+/// Several listeners can share a project, e.g. to accept it over IPv4 and IPv6. The first
+/// one sets the project's color; later ones take it over, or must repeat it if they give one.
+fn add_listener(listeners: &mut Vec<Listener>, entry: &str, index: usize) -> Result<(), String> {
+    let mut listener = Listener::parse(entry, index)?;
+    if listeners.iter().any(|l| l.addr == listener.addr) {
+        return Err(format!("Duplicate listener address: {entry}"));
+    }
+    if let Some(project) = listener.project.as_mut() {
+        let earlier = listeners
+            .iter()
+            .filter_map(|l| l.project.as_ref())
+            .find(|earlier| earlier.name == project.name);
+        match earlier {
+            Some(earlier) if !entry.contains('#') => project.color = earlier.color,
+            Some(earlier) if earlier.color != project.color => {
+                return Err(format!(
+                    "Listener project {} already has color #{:06x}: {entry}",
+                    earlier.name, earlier.color
+                ))
+            }
+            _ => {}
+        }
+    }
+    listeners.push(listener);
+    Ok(())
+}
+
+fn parse_bind(raw: &str) -> Option<SocketAddr> {
+    raw.parse().ok().or_else(|| {
+        let host: IpAddr = raw.trim_matches(['[', ']']).parse().ok()?;
+        Some(SocketAddr::new(host, DEFAULT_PORT))
+    })
+}
+
+/// Compare without returning early, so response timing doesn't leak the token
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::header::HeaderValue;
+
+    #[test]
+    fn test_allowlist_and_token() {
+        let config = ServerConfig {
+            token: Some("s3cret".to_string()),
+            allowlist: vec![
+                IpNetwork::parse("10.1.0.0/16").unwrap(),
+                IpNetwork::parse("fd00::/8").unwrap(),
+                IpNetwork::parse("192.168.1.7").unwrap(),
+            ],
+            ..ServerConfig::default()
+        };
+
+        assert!(config.is_allowed("10.1.200.3".parse().unwrap()));
+        assert!(config.is_allowed("::ffff:10.1.0.1".parse().unwrap()));
+        assert!(config.is_allowed("fd12::1".parse().unwrap()));
+        assert!(config.is_allowed("192.168.1.7".parse().unwrap()));
+        assert!(!config.is_allowed("10.2.0.1".parse().unwrap()));
+        assert!(!config.is_allowed("192.168.1.8".parse().unwrap()));
+        assert!(IpNetwork::parse("10.0.0.0/33").is_err());
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(config.check_token(&headers, None), Err("missing token"));
+        assert_eq!(config.check_token(&headers, Some("token=s3cret")), Ok(()));
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer nope"));
+        assert_eq!(config.check_token(&headers, None), Err("invalid token"));
+        headers.remove(AUTHORIZATION);
+        headers.insert(TOKEN_HEADER, HeaderValue::from_static("s3cret"));
+        assert_eq!(config.check_token(&headers, None), Ok(()));
+
+        assert_eq!(
+            parse_bind("0.0.0.0"),
+            Some(SocketAddr::from(([0, 0, 0, 0], DEFAULT_PORT)))
+        );
+        assert_eq!(parse_bind("[::]:9000"), "[::]:9000".parse().ok());
+
+        let listener = Listener::parse("shop = 0.0.0.0:9001#F59E0B", 0).unwrap();
+        assert_eq!(listener.addr, SocketAddr::from(([0, 0, 0, 0], 9001)));
+        assert_eq!(
+            listener.project,
+            Some(Project {
+                name: "shop".to_string(),
+                color: 0xf59e0b
+            })
+        );
+        let listener = Listener::parse("api=127.0.0.1", 1).unwrap();
+        assert_eq!(listener.addr.port(), DEFAULT_PORT);
+        assert_eq!(listener.project.unwrap().color, PROJECT_COLORS[1]);
+        for raw in [
+            "127.0.0.1:9001",
+            "=127.0.0.1",
+            "api=nowhere",
+            "api=127.0.0.1#red",
+        ] {
+            assert!(Listener::parse(raw, 0).is_err(), "{raw}");
+        }
+    }
+
+    #[test]
+    fn test_listeners_share_projects() {
+        let mut listeners = Vec::new();
+        for (index, entry) in [
+            "shop=127.0.0.1:1#f59e0b",
+            "shop=[::1]:1",
+            "shop=127.0.0.1:2#F59E0B",
+            "api=127.0.0.1:3",
+            "api=[::1]:3",
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            add_listener(&mut listeners, entry, index).unwrap();
+        }
+        let colors: Vec<_> = listeners
+            .iter()
+            .map(|l| l.project.as_ref().unwrap().color)
+            .collect();
+        assert_eq!(colors[..3], [0xf59e0b; 3]);
+        assert_eq!(colors[3..], [PROJECT_COLORS[3]; 2]);
+
+        // A different color for the same name, or a reused address, is a real conflict
+        for entry in ["shop=127.0.0.1:4#3b82f6", "other=[::1]:1"] {
+            assert!(add_listener(&mut listeners, entry, 5).is_err(), "{entry}");
+        }
+        assert_eq!(listeners.len(), 5);
+
+        let config = ServerConfig {
+            listeners,
+            ..ServerConfig::default()
+        };
+        let names: Vec<_> = config.projects().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["shop", "api"]);
+    }
+}
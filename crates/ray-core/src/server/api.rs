@@ -0,0 +1,265 @@
+// server/api.rs - read-only JSON API over the stored events
+use super::queue::IngestQueue;
+use crate::config::config;
+use crate::event_filter::EventFilter;
+use crate::event_storage::EventStorage;
+use crate::events::{registry, EventType};
+use crate::metrics::Exposition;
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{body::Incoming, Method, Request, Response, StatusCode};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+const DEFAULT_PAGE_SIZE: usize = 100;
+const MAX_PAGE_SIZE: usize = 1000;
+
+pub async fn handle_api_request(
+    req: Request<Incoming>,
+    event_storage: &Arc<EventStorage>,
+    queue: &IngestQueue,
+) -> Response<Full<Bytes>> {
+    let path = req.uri().path().trim_end_matches('/');
+    let segments: Vec<&str> = path.split('/').skip(2).collect();
+
+    match (req.method(), segments.as_slice()) {
+        (&Method::GET, ["events"]) => list_events(req.uri().query(), event_storage),
+        (&Method::DELETE, ["events"]) => {
+            event_storage.clear_events();
+            event_storage.info("Api", "Cleared all events");
+            json_response(StatusCode::OK, json!({ "cleared": true }))
+        }
+        (&Method::GET, ["events", id]) => match id.parse::<u64>() {
+            Ok(id) => match event_storage.get_event(id) {
+                Some(entry) => json_response(StatusCode::OK, entry.to_json()),
+                None => error_response(StatusCode::NOT_FOUND, &format!("Event {id} not found")),
+            },
+            Err(_) => error_response(StatusCode::BAD_REQUEST, &format!("Invalid event id: {id}")),
+        },
+        (&Method::GET, ["stats"]) => {
+            let stats = event_storage.get_stats();
+            let queue = queue.stats();
+            json_response(
+                StatusCode::OK,
+                json!({
+                    "total": stats.total,
+                    "by_type": stats.by_type,
+                    "exception_groups": stats.exception_groups,
+                    "generation": stats.generation,
+                    "queue": {
+                        "depth": queue.depth,
+                        "capacity": queue.capacity,
+                        "workers": queue.workers,
+                        "queued": queue.queued,
+                        "dropped": queue.dropped,
+                    },
+                }),
+            )
+        }
+        (&Method::GET, ["types"]) => json_response(StatusCode::OK, list_types()),
+        (&Method::GET, ["logs"]) => json_response(StatusCode::OK, list_logs(event_storage)),
+        (_, ["events"] | ["events", _] | ["stats"] | ["types"] | ["logs"]) => {
+            error_response(StatusCode::METHOD_NOT_ALLOWED, "Method Not Allowed")
+        }
+        _ => error_response(StatusCode::NOT_FOUND, "Not Found"),
+    }
+}
+
+/// `GET /metrics`: storage and server health, plus the ingestion queue, for Prometheus
+pub(super) fn metrics_response(
+    event_storage: &EventStorage,
+    queue: &IngestQueue,
+) -> Response<Full<Bytes>> {
+    let mut out = Exposition::new();
+    event_storage.metrics().render(&mut out);
+
+    let queue = queue.stats();
+    out.gauge(
+        "ray_queue_depth",
+        "Payloads waiting for an ingestion worker",
+        queue.depth as u64,
+    );
+    out.gauge(
+        "ray_queue_capacity",
+        "Payloads the ingestion queue holds",
+        queue.capacity as u64,
+    );
+    out.counter(
+        "ray_queue_dropped_total",
+        "Payloads refused because the ingestion queue was full",
+        queue.dropped,
+    );
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("content-type", Exposition::CONTENT_TYPE)
+        .body(Full::new(Bytes::from(out.finish())))
+        .unwrap()
+}
+
+/// The server's own diagnostics, newest first
+fn list_logs(event_storage: &EventStorage) -> Value {
+    let logs: Vec<Value> = event_storage
+        .internal_log()
+        .into_iter()
+        .map(|record| {
+            json!({
+                "timestamp": record.timestamp,
+                "level": record.level.as_str(),
+                "source": record.source,
+                "message": record.message,
+            })
+        })
+        .collect();
+    json!({ "logs": logs })
+}
+
+/// `GET /api/events?type=log,exception&search=text&project=shop&since=2024-01-01 10:00&cursor=42&limit=50`
+fn list_events(query: Option<&str>, event_storage: &EventStorage) -> Response<Full<Bytes>> {
+    let params = match ListParams::parse(query.unwrap_or("")) {
+        Ok(params) => params,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, &e),
+    };
+
+    let (events, next_cursor) =
+        event_storage.query_events(&params.filter, params.cursor, params.limit);
+
+    json_response(
+        StatusCode::OK,
+        json!({
+            "events": events.iter().map(|entry| entry.to_json()).collect::<Vec<Value>>(),
+            "next_cursor": next_cursor,
+        }),
+    )
+}
+
+/// `GET /api/types`: every event type with the payload type names it covers,
+/// plus the field mappings of declarative custom types and the `RAY_VENDOR_PATTERNS`
+/// front-ends use to collapse vendor frames
+fn list_types() -> Value {
+    let types: Vec<Value> = EventType::all()
+        .into_iter()
+        .map(|event_type| {
+            let fields: Vec<Value> = registry()
+                .definition(event_type.as_str())
+                .map(|definition| {
+                    definition
+                        .fields
+                        .iter()
+                        .map(|field| json!({ "label": field.label, "path": field.path }))
+                        .collect()
+                })
+                .unwrap_or_default();
+            json!({
+                "name": event_type.as_str(),
+                "label": event_type.display_name(),
+                "aliases": event_type.names(),
+                "fields": fields,
+            })
+        })
+        .collect();
+    json!({
+        "types": types,
+        "vendor_patterns": config().frames.vendor_patterns,
+    })
+}
+
+#[derive(Debug)]
+struct ListParams {
+    filter: EventFilter,
+    /// Only entries with a smaller id, i.e. older than the previous page
+    cursor: Option<u64>,
+    limit: usize,
+}
+
+impl ListParams {
+    fn parse(query: &str) -> Result<Self, String> {
+        let mut params = Self {
+            filter: EventFilter::new(),
+            cursor: None,
+            limit: DEFAULT_PAGE_SIZE,
+        };
+        let mut types: Vec<EventType> = Vec::new();
+        let mut projects: Vec<String> = Vec::new();
+
+        for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+            match key.as_ref() {
+                "type" => {
+                    for name in value.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                        types.push(name.parse()?);
+                    }
+                }
+                "project" => projects.extend(
+                    value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|n| !n.is_empty())
+                        .map(str::to_string),
+                ),
+                "search" => params.filter = params.filter.with_search(value.as_ref()),
+                "since" => params.filter = params.filter.with_since(value.as_ref()),
+                "cursor" => {
+                    params.cursor = Some(
+                        value
+                            .parse()
+                            .map_err(|_| format!("Invalid cursor: {value}"))?,
+                    )
+                }
+                "limit" => {
+                    let limit: usize = value
+                        .parse()
+                        .map_err(|_| format!("Invalid limit: {value}"))?;
+                    params.limit = limit.clamp(1, MAX_PAGE_SIZE);
+                }
+                _ => {}
+            }
+        }
+
+        if !types.is_empty() {
+            params.filter = params.filter.with_types(types);
+        }
+        if !projects.is_empty() {
+            params.filter = params.filter.with_projects(projects);
+        }
+
+        Ok(params)
+    }
+}
+
+pub(super) fn json_response(status: StatusCode, body: Value) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(Full::new(Bytes::from(body.to_string())))
+        .unwrap()
+}
+
+pub(super) fn error_response(status: StatusCode, message: &str) -> Response<Full<Bytes>> {
+    json_response(status, json!({ "error": message }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_params_parse() {
+        let params =
+            ListParams::parse("type=log,request&search=payment%20failed&cursor=42&limit=5000")
+                .unwrap();
+
+        assert_eq!(params.cursor, Some(42));
+        assert_eq!(params.limit, MAX_PAGE_SIZE);
+        assert_eq!(params.filter.search.as_deref(), Some("payment failed"));
+        assert_eq!(
+            params.filter.types,
+            Some([EventType::Log, EventType::Http].into_iter().collect())
+        );
+        assert!(ListParams::parse("type=bogus").is_err());
+
+        let params = ListParams::parse("project=shop,%20api").unwrap();
+        let projects = params.filter.projects.unwrap();
+        assert_eq!(projects.len(), 2);
+        assert!(projects.contains("shop") && projects.contains("api"));
+    }
+}
@@ -0,0 +1,178 @@
+// server/encoding.rs - compressed request bodies (Content-Encoding)
+use bytes::Bytes;
+use flate2::read::{DeflateDecoder, MultiGzDecoder, ZlibDecoder};
+use hyper::header::{HeaderMap, CONTENT_ENCODING};
+use hyper::StatusCode;
+use std::fmt;
+use std::io::Read;
+
+/// Sent in `Accept-Encoding` when an encoding is refused
+pub const SUPPORTED: &str = "gzip, deflate, zstd";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Gzip,
+    Deflate,
+    Zstd,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// An encoding we can't decode
+    Unsupported(String),
+    /// The decoded body would exceed the limit
+    TooLarge(usize),
+    /// The body isn't valid for its encoding
+    Invalid(String),
+}
+
+impl DecodeError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::Unsupported(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Self::TooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::Invalid(_) => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported(name) => write!(f, "unsupported content encoding: {name}"),
+            Self::TooLarge(max) => write!(f, "decompressed body exceeds {max} bytes"),
+            Self::Invalid(e) => write!(f, "could not decompress body: {e}"),
+        }
+    }
+}
+
+/// The encodings listed in `Content-Encoding`, in the order they were applied
+///
+/// Checked before the body is read, so unsupported ones are refused without buffering it.
+pub fn parse(headers: &HeaderMap) -> Result<Vec<Encoding>, DecodeError> {
+    let mut encodings = Vec::new();
+    for value in headers.get_all(CONTENT_ENCODING) {
+        let value = value.to_str().map_err(|_| {
+            DecodeError::Unsupported(String::from_utf8_lossy(value.as_bytes()).into())
+        })?;
+        for name in value
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            match name.to_ascii_lowercase().as_str() {
+                "identity" => {}
+                "gzip" | "x-gzip" => encodings.push(Encoding::Gzip),
+                "deflate" => encodings.push(Encoding::Deflate),
+                "zstd" => encodings.push(Encoding::Zstd),
+                _ => return Err(DecodeError::Unsupported(name.to_string())),
+            }
+        }
+    }
+    Ok(encodings)
+}
+
+/// Undo the encodings, last applied first, never producing more than `max_decoded` bytes
+pub fn decode(
+    encodings: &[Encoding],
+    body: Bytes,
+    max_decoded: usize,
+) -> Result<Bytes, DecodeError> {
+    encodings.iter().rev().try_fold(body, |body, encoding| {
+        let decoded = match encoding {
+            Encoding::Gzip => read_limited(MultiGzDecoder::new(&body[..]), max_decoded),
+            // Meant to be zlib-wrapped, but some clients send raw deflate streams
+            Encoding::Deflate if has_zlib_header(&body) => {
+                read_limited(ZlibDecoder::new(&body[..]), max_decoded)
+            }
+            Encoding::Deflate => read_limited(DeflateDecoder::new(&body[..]), max_decoded),
+            Encoding::Zstd => zstd::stream::read::Decoder::new(&body[..])
+                .map_err(|e| DecodeError::Invalid(e.to_string()))
+                .and_then(|decoder| read_limited(decoder, max_decoded)),
+        }?;
+        Ok(Bytes::from(decoded))
+    })
+}
+
+/// Read at most one byte past the limit, so a zip bomb is stopped early
+fn read_limited(reader: impl Read, max_decoded: usize) -> Result<Vec<u8>, DecodeError> {
+    let mut decoded = Vec::new();
+    reader
+        .take(max_decoded as u64 + 1)
+        .read_to_end(&mut decoded)
+        .map_err(|e| DecodeError::Invalid(e.to_string()))?;
+    if decoded.len() > max_decoded {
+        return Err(DecodeError::TooLarge(max_decoded));
+    }
+    Ok(decoded)
+}
+
+/// RFC 1950: deflate method, and the first two bytes are a multiple of 31
+fn has_zlib_header(body: &[u8]) -> bool {
+    match body {
+        [cmf, flg, ..] => cmf & 0x0f == 8 && (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
+    use flate2::Compression;
+    use hyper::header::HeaderValue;
+    use std::io::Write;
+
+    #[test]
+    fn test_decode_encodings_and_limit() {
+        let json = br#"{"payloads":[]}"#;
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(json).unwrap();
+        let gzip = Bytes::from(gzip.finish().unwrap());
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(json).unwrap();
+        let zlib = Bytes::from(zlib.finish().unwrap());
+        let mut raw = DeflateEncoder::new(Vec::new(), Compression::default());
+        raw.write_all(json).unwrap();
+        let raw = Bytes::from(raw.finish().unwrap());
+        let zstd = Bytes::from(zstd::encode_all(&json[..], 0).unwrap());
+
+        assert_eq!(
+            decode(&[Encoding::Gzip], gzip.clone(), 1024).unwrap(),
+            &json[..]
+        );
+        assert_eq!(decode(&[Encoding::Deflate], zlib, 1024).unwrap(), &json[..]);
+        assert_eq!(decode(&[Encoding::Deflate], raw, 1024).unwrap(), &json[..]);
+        assert_eq!(decode(&[Encoding::Zstd], zstd, 1024).unwrap(), &json[..]);
+
+        // Applied gzip first, then zstd
+        let layered = Bytes::from(zstd::encode_all(&gzip[..], 0).unwrap());
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_ENCODING, HeaderValue::from_static("gzip, ZSTD"));
+        let encodings = parse(&headers).unwrap();
+        assert_eq!(encodings, [Encoding::Gzip, Encoding::Zstd]);
+        assert_eq!(decode(&encodings, layered, 1024).unwrap(), &json[..]);
+
+        // A megabyte of zeros compresses to a few dozen bytes
+        let bomb = zstd::encode_all(&vec![0u8; 1 << 20][..], 19).unwrap();
+        assert!(bomb.len() < 1024);
+        assert_eq!(
+            decode(&[Encoding::Zstd], Bytes::from(bomb), 1024),
+            Err(DecodeError::TooLarge(1024))
+        );
+        assert_eq!(
+            decode(&[Encoding::Gzip], Bytes::from_static(b"not gzip"), 1024)
+                .unwrap_err()
+                .status(),
+            StatusCode::BAD_REQUEST
+        );
+
+        headers.insert(CONTENT_ENCODING, HeaderValue::from_static("br"));
+        assert_eq!(
+            parse(&headers),
+            Err(DecodeError::Unsupported("br".to_string()))
+        );
+        headers.insert(CONTENT_ENCODING, HeaderValue::from_static("identity"));
+        assert_eq!(parse(&headers), Ok(Vec::new()));
+    }
+}
@@ -0,0 +1,471 @@
+// server/forward.rs - relays received request bodies to other Ray receivers
+use crate::config::env_flag;
+use crate::event_storage::EventStorage;
+use crate::redaction::RedactionConfig;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use hyper::{Request, StatusCode, Uri};
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Ids of the instances a request was forwarded by, oldest first
+pub const FORWARDED_BY: &str = "x-ray-forwarded-by";
+
+/// Request bodies waiting per target when `RAY_FORWARD_BUFFER` is not set
+const DEFAULT_BUFFER: usize = 1000;
+
+/// Attempts after the first failed one when `RAY_FORWARD_RETRIES` is not set
+const DEFAULT_RETRIES: u32 = 5;
+
+const FIRST_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where received requests are relayed to; nothing is forwarded unless this is configured
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForwardConfig {
+    pub targets: Vec<Uri>,
+    /// Only payloads of these types are forwarded; empty forwards everything
+    pub types: Vec<String>,
+    /// Request bodies waiting per target before further ones are dropped
+    pub buffer: usize,
+    /// Attempts after the first one fails
+    pub retries: u32,
+    /// Sent as `Authorization: Bearer` to targets that require a token
+    pub token: Option<String>,
+    /// Send bodies exactly as received, without masking what redaction masks locally
+    pub raw: bool,
+}
+
+impl ForwardConfig {
+    /// Read `RAY_FORWARD_TO` (comma-separated `http://` URLs), `RAY_FORWARD_TYPES`,
+    /// `RAY_FORWARD_BUFFER`, `RAY_FORWARD_RETRIES`, `RAY_FORWARD_TOKEN` and `RAY_FORWARD_RAW`
+    ///
+    /// Also returns the settings that could not be parsed.
+    pub fn from_env() -> (Option<Self>, Vec<String>) {
+        let list = |name: &str| -> Vec<String> {
+            std::env::var(name)
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+
+        let mut errors = Vec::new();
+        let targets: Vec<Uri> = list("RAY_FORWARD_TO")
+            .iter()
+            .filter_map(|raw| parse_target(raw).map_err(|e| errors.push(e)).ok())
+            .collect();
+        let mut number = |name: &str, default: u64| -> u64 {
+            let Ok(raw) = std::env::var(name) else {
+                return default;
+            };
+            raw.trim().parse().unwrap_or_else(|_| {
+                errors.push(format!("Invalid {name}: {raw}"));
+                default
+            })
+        };
+        let buffer = number("RAY_FORWARD_BUFFER", DEFAULT_BUFFER as u64).max(1) as usize;
+        let retries = number("RAY_FORWARD_RETRIES", u64::from(DEFAULT_RETRIES)) as u32;
+
+        if targets.is_empty() {
+            return (None, errors);
+        }
+        let config = Self {
+            targets,
+            types: list("RAY_FORWARD_TYPES"),
+            buffer,
+            retries,
+            token: std::env::var("RAY_FORWARD_TOKEN")
+                .ok()
+                .filter(|token| !token.is_empty()),
+            raw: env_flag("RAY_FORWARD_RAW").unwrap_or(false),
+        };
+        (Some(config), errors)
+    }
+}
+
+fn parse_target(raw: &str) -> Result<Uri, String> {
+    let uri: Uri = raw
+        .parse()
+        .map_err(|_| format!("Invalid RAY_FORWARD_TO target: {raw}"))?;
+    match (uri.scheme_str(), uri.authority()) {
+        (Some("http"), Some(_)) => Ok(uri),
+        _ => Err(format!(
+            "RAY_FORWARD_TO targets must be http:// URLs: {raw}"
+        )),
+    }
+}
+
+/// Sends request bodies on to every target, in the order they were received
+///
+/// Each target has its own buffer and task, so one that is down doesn't hold up
+/// the others: it gets retries with exponential backoff while later bodies wait,
+/// and once its buffer is full further bodies for it are dropped. Requests carry
+/// the ids of the instances they passed through, and one that comes back to this
+/// instance is refused, so instances forwarding to each other don't loop.
+///
+/// Payloads are redacted before they leave, with the same rules as stored ones,
+/// unless `RAY_FORWARD_RAW` asks for the bodies as received.
+pub struct Forwarder {
+    id: String,
+    types: Vec<String>,
+    /// Applied to every forwarded payload; `None` forwards them unchanged
+    redaction: Option<RedactionConfig>,
+    targets: Vec<Target>,
+    storage: Arc<EventStorage>,
+}
+
+struct Target {
+    uri: Uri,
+    sender: mpsc::Sender<Forward>,
+    /// Set while bodies are being dropped, so an outage is logged once
+    dropping: Arc<AtomicBool>,
+}
+
+struct Forward {
+    body: Bytes,
+    hops: HeaderValue,
+}
+
+impl Forwarder {
+    /// Spawn a delivery task per target; must be called within the Tokio runtime
+    pub fn start(
+        config: &ForwardConfig,
+        redaction: &RedactionConfig,
+        storage: Arc<EventStorage>,
+    ) -> Self {
+        let client = Client::builder(TokioExecutor::new()).build_http();
+        let token = config
+            .token
+            .as_ref()
+            .and_then(|token| HeaderValue::from_str(&format!("Bearer {token}")).ok());
+
+        let targets = config
+            .targets
+            .iter()
+            .map(|uri| {
+                let (sender, receiver) = mpsc::channel(config.buffer);
+                let dropping = Arc::new(AtomicBool::new(false));
+                let delivery = Delivery {
+                    uri: uri.clone(),
+                    client: client.clone(),
+                    token: token.clone(),
+                    retries: config.retries,
+                    dropping: Arc::clone(&dropping),
+                    storage: Arc::clone(&storage),
+                };
+                tokio::spawn(delivery.run(receiver));
+                Target {
+                    uri: uri.clone(),
+                    sender,
+                    dropping,
+                }
+            })
+            .collect();
+
+        Self {
+            id: instance_id(),
+            types: config.types.clone(),
+            redaction: (!config.raw && redaction.enabled).then(|| redaction.clone()),
+            targets,
+            storage,
+        }
+    }
+
+    /// Whether the request was already forwarded by this instance
+    pub fn is_loop(&self, headers: &HeaderMap) -> bool {
+        previous_hops(headers)
+            .split(',')
+            .any(|hop| hop.trim() == self.id)
+    }
+
+    /// Queue a decoded request body for every target, keeping only the configured types
+    ///
+    /// `headers` are the ones the body was received with. Parses the body when
+    /// filtering by type or redacting, so keep this off the async workers.
+    pub fn submit(&self, body: &Bytes, headers: &HeaderMap) {
+        let body = if self.types.is_empty() && self.redaction.is_none() {
+            body.clone()
+        } else {
+            match self.prepare(body) {
+                Some(body) => body,
+                None => return,
+            }
+        };
+        let Ok(hops) = HeaderValue::from_str(&self.next_hops(&previous_hops(headers))) else {
+            return;
+        };
+
+        for target in &self.targets {
+            let forward = Forward {
+                body: body.clone(),
+                hops: hops.clone(),
+            };
+            if target.sender.try_send(forward).is_err()
+                && !target.dropping.swap(true, Ordering::Relaxed)
+            {
+                self.storage.warn(
+                    "Forward",
+                    &format!(
+                        "Buffer for {} is full, dropping requests until it catches up",
+                        target.uri
+                    ),
+                );
+            }
+        }
+    }
+
+    /// The body with only the payloads of the configured types, redacted, or `None`
+    /// if none are left
+    fn prepare(&self, body: &[u8]) -> Option<Bytes> {
+        // Parsed as a whole so `uuid` and `meta` are passed on as they are
+        let mut request: Value = serde_json::from_slice(body).ok()?;
+        let payloads = request.get_mut("payloads")?.as_array_mut()?;
+        if !self.types.is_empty() {
+            payloads.retain(|payload| {
+                payload
+                    .get("type")
+                    .and_then(Value::as_str)
+                    .is_some_and(|event_type| self.types.iter().any(|t| t == event_type))
+            });
+        }
+        if payloads.is_empty() {
+            return None;
+        }
+        if let Some(redaction) = &self.redaction {
+            for payload in payloads.iter_mut() {
+                redaction.redact(payload);
+            }
+        }
+        serde_json::to_vec(&request).ok().map(Bytes::from)
+    }
+
+    fn next_hops(&self, previous: &str) -> String {
+        if previous.is_empty() {
+            self.id.clone()
+        } else {
+            format!("{previous}, {}", self.id)
+        }
+    }
+}
+
+fn previous_hops(headers: &HeaderMap) -> String {
+    headers
+        .get_all(FORWARDED_BY)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Random id for this process, so it recognizes requests it forwarded itself
+fn instance_id() -> String {
+    use std::hash::{BuildHasher, Hasher};
+
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u32(std::process::id());
+    format!("{:016x}", hasher.finish())
+}
+
+/// Why a delivery attempt failed
+enum Failure {
+    /// The target may accept the request later
+    Retry(String),
+    /// Sending it again won't help
+    Refused(String),
+}
+
+/// Sends one target's bodies, one at a time
+struct Delivery {
+    uri: Uri,
+    client: Client<HttpConnector, Full<Bytes>>,
+    token: Option<HeaderValue>,
+    retries: u32,
+    dropping: Arc<AtomicBool>,
+    storage: Arc<EventStorage>,
+}
+
+impl Delivery {
+    async fn run(self, mut receiver: mpsc::Receiver<Forward>) {
+        while let Some(forward) = receiver.recv().await {
+            let mut backoff = FIRST_BACKOFF;
+            let mut attempt = 0;
+            loop {
+                match self.send(&forward).await {
+                    Ok(()) => {
+                        if self.dropping.swap(false, Ordering::Relaxed) {
+                            self.storage
+                                .info("Forward", &format!("{} caught up", self.uri));
+                        }
+                        break;
+                    }
+                    Err(Failure::Retry(reason)) if attempt < self.retries => {
+                        self.storage.debug(
+                            "Forward",
+                            &format!("Retrying {} in {backoff:?}: {reason}", self.uri),
+                        );
+                        tokio::time::sleep(backoff).await;
+                        backoff = (backoff * 2).min(MAX_BACKOFF);
+                        attempt += 1;
+                    }
+                    Err(Failure::Retry(reason)) => {
+                        self.storage.error(
+                            "Forward",
+                            &format!(
+                                "Gave up forwarding to {} after {} attempts: {reason}",
+                                self.uri,
+                                attempt + 1
+                            ),
+                        );
+                        break;
+                    }
+                    Err(Failure::Refused(reason)) => {
+                        self.storage.error(
+                            "Forward",
+                            &format!("{} refused a forwarded request: {reason}", self.uri),
+                        );
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn send(&self, forward: &Forward) -> Result<(), Failure> {
+        let mut request = Request::post(self.uri.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .header(FORWARDED_BY, forward.hops.clone());
+        if let Some(token) = &self.token {
+            request = request.header(AUTHORIZATION, token.clone());
+        }
+        let request = request
+            .body(Full::new(forward.body.clone()))
+            .map_err(|e| Failure::Refused(e.to_string()))?;
+
+        let response =
+            match tokio::time::timeout(REQUEST_TIMEOUT, self.client.request(request)).await {
+                Ok(Ok(response)) => response,
+                Ok(Err(e)) => return Err(Failure::Retry(e.to_string())),
+                Err(_) => return Err(Failure::Retry("timed out".to_string())),
+            };
+        let status = response.status();
+        // Read to the end so the connection can be reused
+        let _ = response.into_body().collect().await;
+
+        match status {
+            // Received; payloads the target couldn't process are its own business
+            status if status.is_success() || status == StatusCode::UNPROCESSABLE_ENTITY => Ok(()),
+            StatusCode::LOOP_DETECTED => Err(Failure::Refused(status.to_string())),
+            StatusCode::REQUEST_TIMEOUT | StatusCode::TOO_MANY_REQUESTS => {
+                Err(Failure::Retry(status.to_string()))
+            }
+            status if status.is_server_error() => Err(Failure::Retry(status.to_string())),
+            status => Err(Failure::Refused(status.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_and_loop_detection() {
+        let storage = Arc::new(EventStorage::new());
+        storage.set_console_output(false);
+        let config = ForwardConfig {
+            targets: Vec::new(),
+            types: vec!["exception".to_string()],
+            buffer: 1,
+            retries: 0,
+            token: None,
+            raw: false,
+        };
+        let forwarder = Forwarder::start(&config, &RedactionConfig::default(), storage);
+
+        let body =
+            br#"{"uuid": "x", "payloads": [{"type": "log"}, {"type": "exception"}], "meta": {}}"#;
+        let filtered: Value = serde_json::from_slice(&forwarder.prepare(body).unwrap()).unwrap();
+        assert_eq!(
+            filtered,
+            serde_json::json!({"uuid": "x", "payloads": [{"type": "exception"}], "meta": {}})
+        );
+        assert_eq!(
+            forwarder.prepare(br#"{"payloads": [{"type": "log"}]}"#),
+            None
+        );
+
+        // Another instance's request is relayed, one that already passed through here isn't
+        let mut headers = HeaderMap::new();
+        headers.insert(FORWARDED_BY, HeaderValue::from_static("0123456789abcdef"));
+        assert!(!forwarder.is_loop(&headers));
+        let hops = forwarder.next_hops(&previous_hops(&headers));
+        assert_eq!(hops, format!("0123456789abcdef, {}", forwarder.id));
+        headers.insert(FORWARDED_BY, HeaderValue::from_str(&hops).unwrap());
+        assert!(forwarder.is_loop(&headers));
+
+        assert!(parse_target("http://127.0.0.1:23518").is_ok());
+        assert!(parse_target("https://ray.example.com").is_err());
+        assert!(parse_target("localhost").is_err());
+    }
+
+    #[test]
+    fn test_upstream_receives_redacted_payloads() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let upstream = runtime
+            .block_on(tokio::net::TcpListener::bind("127.0.0.1:0"))
+            .unwrap();
+        let target = format!("http://{}/", upstream.local_addr().unwrap());
+        let received = runtime.spawn(async move {
+            let (mut stream, _) = upstream.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 4096];
+            while !request.ends_with(b"}") {
+                let read = stream.read(&mut buffer).await.unwrap();
+                assert!(read > 0, "forwarded request ended early");
+                request.extend_from_slice(&buffer[..read]);
+            }
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                .await;
+            String::from_utf8_lossy(&request).into_owned()
+        });
+
+        let storage = Arc::new(EventStorage::new());
+        storage.set_console_output(false);
+        let config = ForwardConfig {
+            targets: vec![parse_target(&target).unwrap()],
+            types: Vec::new(),
+            buffer: 1,
+            retries: 0,
+            token: None,
+            raw: false,
+        };
+        let forwarder = {
+            let _runtime = runtime.enter();
+            Forwarder::start(&config, &RedactionConfig::default(), storage)
+        };
+        let body = br#"{"payloads": [{"type": "log", "content": {"password": "hunter2"}}]}"#;
+        forwarder.submit(&Bytes::from_static(body), &HeaderMap::new());
+
+        let timeout = Duration::from_secs(5);
+        let request = runtime
+            .block_on(async { tokio::time::timeout(timeout, received).await })
+            .expect("nothing was forwarded")
+            .unwrap();
+        assert!(!request.contains("hunter2"), "{request}");
+        assert!(request.contains(crate::redaction::REDACTED), "{request}");
+    }
+}
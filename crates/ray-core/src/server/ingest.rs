@@ -0,0 +1,215 @@
+// server/ingest.rs - parses a request body one payload at a time and reports on the batch
+use crate::event_storage::PayloadStatus;
+use hyper::StatusCode;
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde_json::{json, Value};
+use std::fmt;
+
+/// What the client is told about one payload of its request
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PayloadResult {
+    Processed(PayloadStatus),
+    /// Dropped because the ingestion queue was full; worth retrying
+    QueueFull,
+    /// Queued, but no status came back, e.g. because the server is shutting down
+    Unknown,
+}
+
+impl PayloadResult {
+    fn to_json(&self) -> Value {
+        match self {
+            Self::Processed(PayloadStatus::Accepted) => json!({"status": "accepted"}),
+            Self::Processed(PayloadStatus::UnknownType(event_type)) => {
+                json!({"status": "unknown_type", "type": event_type})
+            }
+            Self::Processed(PayloadStatus::Dropped { script }) => {
+                json!({"status": "dropped", "script": script})
+            }
+            Self::Processed(PayloadStatus::Failed(reason)) => {
+                json!({"status": "failed", "reason": reason})
+            }
+            Self::QueueFull => json!({"status": "queue_full"}),
+            Self::Unknown => json!({"status": "unknown"}),
+        }
+    }
+
+    /// Stored, even if only as an unknown event
+    fn is_stored(&self) -> bool {
+        matches!(
+            self,
+            Self::Processed(PayloadStatus::Accepted | PayloadStatus::UnknownType(_))
+        )
+    }
+
+    fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            Self::Processed(PayloadStatus::Failed(_)) | Self::QueueFull | Self::Unknown
+        )
+    }
+}
+
+/// Status code and JSON body summarizing a request's payloads, in request order
+///
+/// `200` when nothing failed, `207` when some did, `422` when all did and `503`
+/// when the queue was full, so the client knows to retry later.
+pub fn batch_response(results: &[PayloadResult]) -> (StatusCode, Value) {
+    let stored = results.iter().filter(|r| r.is_stored()).count();
+    let failed = results.iter().filter(|r| r.is_failure()).count();
+
+    let status = if results.contains(&PayloadResult::QueueFull) {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else if failed == 0 {
+        StatusCode::OK
+    } else if failed == results.len() {
+        StatusCode::UNPROCESSABLE_ENTITY
+    } else {
+        StatusCode::MULTI_STATUS
+    };
+
+    let body = json!({
+        "received": results.len(),
+        "stored": stored,
+        "failed": failed,
+        "results": results.iter().map(PayloadResult::to_json).collect::<Vec<_>>(),
+    });
+    (status, body)
+}
+
+/// Parse `{"payloads": [...]}` and hand each payload to `on_payload` as soon as it
+/// is complete, returning how many there were
+///
+/// Only one payload is held in memory at a time and the body is read in place,
+/// without copying it into a `String` first. Payloads before a syntax error have
+/// already been handed over when the error is returned. Structure problems, like
+/// a missing `payloads` array, are reported as data errors (`Error::is_data`).
+pub fn stream_payloads<F>(body: &[u8], on_payload: F) -> serde_json::Result<usize>
+where
+    F: FnMut(Value),
+{
+    let mut deserializer = serde_json::Deserializer::from_slice(body);
+    let count = deserializer.deserialize_map(Batch { on_payload })?;
+    deserializer.end()?;
+    Ok(count)
+}
+
+/// The request object; everything but `payloads` (like `uuid` and `meta`) is skipped
+struct Batch<F> {
+    on_payload: F,
+}
+
+impl<'de, F: FnMut(Value)> Visitor<'de> for Batch<F> {
+    type Value = usize;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an object with a payloads array")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<usize, A::Error> {
+        let mut count = None;
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "payloads" {
+                count = Some(map.next_value_seed(Payloads {
+                    on_payload: &mut self.on_payload,
+                })?);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        count.ok_or_else(|| de::Error::missing_field("payloads"))
+    }
+}
+
+struct Payloads<'a, F> {
+    on_payload: &'a mut F,
+}
+
+impl<'de, F: FnMut(Value)> DeserializeSeed<'de> for Payloads<'_, F> {
+    type Value = usize;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<usize, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, F: FnMut(Value)> Visitor<'de> for Payloads<'_, F> {
+    type Value = usize;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of payloads")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<usize, A::Error> {
+        let mut count = 0;
+        while let Some(payload) = seq.next_element::<Value>()? {
+            (self.on_payload)(payload);
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_stream_payloads() {
+        let mut seen = Vec::new();
+        let body = br#"{"uuid": "x", "payloads": [{"type": "log"}, {"type": "html"}], "meta": {}}"#;
+        assert_eq!(stream_payloads(body, |p| seen.push(p)).unwrap(), 2);
+        assert_eq!(seen, [json!({"type": "log"}), json!({"type": "html"})]);
+
+        // Payloads before a syntax error are already processed
+        seen.clear();
+        let error = stream_payloads(br#"{"payloads": [{"type": "log"}, {"#, |p| seen.push(p));
+        assert!(error.unwrap_err().is_eof());
+        assert_eq!(seen.len(), 1);
+
+        for body in [&br#"{"uuid": "x"}"#[..], br#"{"payloads": {}}"#, b"[]"] {
+            assert!(stream_payloads(body, |_| {}).unwrap_err().is_data());
+        }
+        assert!(stream_payloads(br#"{"payloads": []} x"#, |_| {})
+            .unwrap_err()
+            .is_syntax());
+        assert!(stream_payloads(b"{\"payloads\": [\"\xff\"]}", |_| {})
+            .unwrap_err()
+            .is_syntax());
+    }
+
+    #[test]
+    fn test_batch_response() {
+        let accepted = PayloadResult::Processed(PayloadStatus::Accepted);
+        let failed = PayloadResult::Processed(PayloadStatus::Failed("bad".to_string()));
+        let dropped = PayloadResult::Processed(PayloadStatus::Dropped {
+            script: "drop.rhai".to_string(),
+        });
+
+        let (status, body) = batch_response(&[accepted.clone(), dropped]);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            json!({
+                "received": 2, "stored": 1, "failed": 0,
+                "results": [{"status": "accepted"}, {"status": "dropped", "script": "drop.rhai"}]
+            })
+        );
+
+        let (status, body) = batch_response(&[accepted.clone(), failed.clone()]);
+        assert_eq!(status, StatusCode::MULTI_STATUS);
+        assert_eq!(
+            body["results"][1],
+            json!({"status": "failed", "reason": "bad"})
+        );
+        assert_eq!(
+            batch_response(&[failed]).0,
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert_eq!(
+            batch_response(&[accepted, PayloadResult::QueueFull]).0,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(batch_response(&[]).0, StatusCode::OK);
+    }
+}
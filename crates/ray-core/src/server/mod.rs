@@ -0,0 +1,638 @@
+// server/mod.rs
+mod access;
+mod api;
+mod encoding;
+mod forward;
+mod ingest;
+mod queue;
+mod stream;
+mod tls;
+mod web_ui;
+
+pub use access::{IpNetwork, Listener, Project, ServerConfig, DEFAULT_PORT};
+pub use forward::{ForwardConfig, Forwarder, FORWARDED_BY};
+pub use ingest::{batch_response, stream_payloads, PayloadResult};
+pub use queue::{IngestQueue, QueueStats};
+pub use tls::TlsConfig;
+
+use crate::config::config;
+use crate::event_storage::EventStorage;
+use bytes::Bytes;
+use futures_util::future::select_all;
+use http_body_util::combinators::BoxBody;
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use hyper::header::{HeaderMap, HeaderValue, ACCEPT_ENCODING, CONTENT_LENGTH, RETRY_AFTER};
+use hyper::service::service_fn;
+use hyper::{body::Incoming, Request, Response, StatusCode, Uri, Version};
+use hyper_util::rt::{TokioExecutor, TokioIo, TokioTimer};
+use hyper_util::server::conn::auto;
+use hyper_util::server::graceful::{GracefulShutdown, Watcher};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{oneshot, watch, Semaphore};
+
+/// Body type for every response: buffered ones and live streams alike
+pub type ServerBody = BoxBody<Bytes, Infallible>;
+
+const HTTP2_PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Pause after a failed accept, so an error that persists doesn't spin the loop
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_millis(100);
+
+pub async fn start_server(
+    event_storage: Arc<EventStorage>,
+    mut shutdown_rx: oneshot::Receiver<()>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let server_config = &config().server;
+    for error in &server_config.errors {
+        event_storage.error("Server", error);
+    }
+
+    let tls = match &server_config.tls {
+        Some(tls_config) => {
+            let (acceptor, generated) = tls_config.acceptor(server_config.http2)?;
+            if let Some(message) = generated {
+                event_storage.info("Server", &message);
+            }
+            Some(acceptor)
+        }
+        None => None,
+    };
+    let scheme = if tls.is_some() { "https" } else { "http" };
+
+    if server_config.listeners.is_empty() {
+        return Err("No listeners configured".into());
+    }
+    let mut listeners = Vec::with_capacity(server_config.listeners.len());
+    let mut projects: Vec<Option<Arc<str>>> = Vec::with_capacity(listeners.capacity());
+    let mut addresses = Vec::with_capacity(listeners.capacity());
+    for listener_config in &server_config.listeners {
+        let addr = listener_config.addr;
+        listeners.push(TcpListener::bind(addr).await?);
+        let project = listener_config.project.as_ref().map(|p| p.name.as_str());
+        projects.push(project.map(Arc::from));
+        addresses.push(match project {
+            Some(project) => format!("{scheme}://{addr} ({project})"),
+            None => format!("{scheme}://{addr}"),
+        });
+    }
+
+    let server_msg = format!("Server listening on {}", addresses.join(", "));
+
+    // Store server info in event storage for display
+    event_storage.set_server_info(server_msg.clone());
+    // Only log this once at startup
+    for address in &addresses {
+        event_storage.info("Server", &format!("Started and listening on {address}"));
+    }
+
+    // Payloads are stored by workers, so request handlers never wait on processing
+    let queue = Arc::new(IngestQueue::start(
+        Arc::clone(&event_storage),
+        server_config.queue_capacity,
+        server_config.workers,
+        server_config.batch_size,
+    )?);
+
+    let forwarder = server_config.forward.as_ref().map(|forward_config| {
+        let targets: Vec<String> = forward_config.targets.iter().map(Uri::to_string).collect();
+        event_storage.info(
+            "Forward",
+            &format!("Forwarding requests to {}", targets.join(", ")),
+        );
+        Arc::new(Forwarder::start(
+            forward_config,
+            &config().redaction,
+            Arc::clone(&event_storage),
+        ))
+    });
+
+    let builder = Arc::new(connection_builder(server_config));
+    let connections = Arc::new(Semaphore::new(server_config.max_connections));
+    let graceful = GracefulShutdown::new();
+    // Tells live streams to end, so they don't hold up the drain below
+    let (streams_tx, streams_rx) = watch::channel(false);
+
+    loop {
+        tokio::select! {
+            // Check for shutdown signal
+            _ = &mut shutdown_rx => {
+                event_storage.info("Server", "Shutting down HTTP server");
+                break;
+            }
+            // Accept new connections
+            (result, index) = accept_any(&listeners) => {
+                match result {
+                    Ok((stream, remote)) => {
+                        let ip = remote.ip();
+                        if !server_config.is_allowed(ip) {
+                            event_storage.error(
+                                "Access",
+                                &format!("Rejected connection from {ip}: not in RAY_ALLOW"),
+                            );
+                            continue;
+                        }
+                        // Held for the lifetime of the connection
+                        let Ok(permit) = Arc::clone(&connections).try_acquire_owned() else {
+                            event_storage.error(
+                                "Server",
+                                &format!(
+                                    "Rejected connection from {ip}: {} connections already open",
+                                    server_config.max_connections
+                                ),
+                            );
+                            continue;
+                        };
+
+                        let connection = Connection {
+                            remote,
+                            storage: Arc::clone(&event_storage),
+                            queue: Arc::clone(&queue),
+                            forwarder: forwarder.clone(),
+                            project: projects[index].clone(),
+                            builder: Arc::clone(&builder),
+                            watcher: graceful.watcher(),
+                            streams: streams_rx.clone(),
+                        };
+                        let tls = tls.clone();
+                        let handshake_timeout = server_config.header_timeout;
+
+                        let metrics_storage = Arc::clone(&event_storage);
+                        metrics_storage.metrics().connection_opened();
+
+                        tokio::task::spawn(async move {
+                            let _permit = permit;
+                            match tls {
+                                // Handshake in the task so a slow client can't stall accepting
+                                Some(acceptor) => {
+                                    let handshake = acceptor.accept(stream);
+                                    match tokio::time::timeout(handshake_timeout, handshake).await {
+                                        Ok(Ok(stream)) => connection.serve(stream).await,
+                                        Ok(Err(e)) => connection.storage.error(
+                                            "Server",
+                                            &format!("TLS handshake with {ip} failed: {e}"),
+                                        ),
+                                        Err(_) => connection.storage.error(
+                                            "Server",
+                                            &format!("TLS handshake with {ip} timed out"),
+                                        ),
+                                    }
+                                }
+                                None => connection.serve(stream).await,
+                            }
+                            metrics_storage.metrics().connection_closed();
+                        });
+                    }
+                    // One listener failing, e.g. out of file descriptors, must not stop the others
+                    Err(e) => {
+                        event_storage.error(
+                            "Server",
+                            &format!("Accept error on {}: {e}", addresses[index]),
+                        );
+                        tokio::time::sleep(ACCEPT_ERROR_DELAY).await;
+                    }
+                }
+            }
+        }
+    }
+
+    // Stop accepting, then let in-flight requests finish
+    drop(listeners);
+    let _ = streams_tx.send(true);
+    let open = graceful.count();
+    if open > 0 {
+        event_storage.info(
+            "Server",
+            &format!("Waiting for {open} open connections to finish"),
+        );
+    }
+    tokio::select! {
+        _ = graceful.shutdown() => {}
+        _ = tokio::time::sleep(server_config.shutdown_timeout) => {
+            event_storage.error(
+                "Server",
+                &format!(
+                    "Connections still open after {}s, closing them",
+                    server_config.shutdown_timeout.as_secs()
+                ),
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Wait for a connection on any of the listeners, along with the index of the one it came in on
+async fn accept_any(
+    listeners: &[TcpListener],
+) -> (std::io::Result<(TcpStream, SocketAddr)>, usize) {
+    let (result, index, _) =
+        select_all(listeners.iter().map(|listener| Box::pin(listener.accept()))).await;
+    (result, index)
+}
+
+/// HTTP/1.1 (with upgrades for WebSocket) and h2c on the same port, tuned from the config
+fn connection_builder(server_config: &ServerConfig) -> auto::Builder<TokioExecutor> {
+    let mut builder = auto::Builder::new(TokioExecutor::new());
+    builder
+        .http1()
+        .timer(TokioTimer::new())
+        .keep_alive(server_config.keep_alive)
+        .header_read_timeout(server_config.header_timeout);
+    builder
+        .http2()
+        .timer(TokioTimer::new())
+        // Pings find dead peers on long-lived HTTP/2 connections
+        .keep_alive_interval(server_config.keep_alive.then_some(HTTP2_PING_INTERVAL))
+        .keep_alive_timeout(server_config.header_timeout);
+    builder
+}
+
+/// What a connection task needs from the accept loop
+struct Connection {
+    remote: SocketAddr,
+    storage: Arc<EventStorage>,
+    queue: Arc<IngestQueue>,
+    forwarder: Option<Arc<Forwarder>>,
+    /// Project events from this listener are tagged with
+    project: Option<Arc<str>>,
+    builder: Arc<auto::Builder<TokioExecutor>>,
+    watcher: Watcher,
+    streams: watch::Receiver<bool>,
+}
+
+impl Connection {
+    async fn serve<S>(self, stream: S)
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let Self {
+            remote,
+            storage,
+            queue,
+            forwarder,
+            project,
+            builder,
+            watcher,
+            streams,
+        } = self;
+
+        let storage_clone = Arc::clone(&storage);
+        let service = service_fn(move |req| {
+            let req_storage = Arc::clone(&storage_clone);
+            let queue = Arc::clone(&queue);
+            let forwarder = forwarder.clone();
+            let project = project.clone();
+            let streams = streams.clone();
+            async move {
+                route_request(req, remote, req_storage, queue, forwarder, project, streams).await
+            }
+        });
+
+        let connection = builder
+            .serve_connection_with_upgrades(TokioIo::new(stream), service)
+            .into_owned();
+        if let Err(err) = watcher.watch(connection).await {
+            let error_msg = format!("Error serving connection: {err:?}");
+            storage.error("Server", &error_msg);
+        }
+    }
+}
+
+async fn route_request(
+    req: Request<Incoming>,
+    remote: SocketAddr,
+    event_storage: Arc<EventStorage>,
+    queue: Arc<IngestQueue>,
+    forwarder: Option<Arc<Forwarder>>,
+    project: Option<Arc<str>>,
+    streams: watch::Receiver<bool>,
+) -> Result<Response<ServerBody>, hyper::Error> {
+    event_storage.metrics().record_request();
+
+    // Prior-knowledge h2c can't be refused before the preface is read, so refuse its requests
+    if req.version() == Version::HTTP_2 && !config().server.http2 {
+        return Ok(Response::builder()
+            .status(StatusCode::HTTP_VERSION_NOT_SUPPORTED)
+            .body(Full::new(Bytes::from("HTTP/2 is disabled")).boxed())
+            .unwrap());
+    }
+
+    let server_config = &config().server;
+    if let Some(response) = require_token(
+        server_config,
+        &event_storage,
+        remote,
+        req.method(),
+        req.uri(),
+        req.headers(),
+    ) {
+        return Ok(response.map(BodyExt::boxed));
+    }
+
+    if req.uri().path() == "/api/stream" {
+        return Ok(stream::handle_stream(req, &event_storage, streams));
+    }
+
+    let response = handle_request(req, remote, event_storage, queue, forwarder, project).await?;
+    Ok(response.map(BodyExt::boxed))
+}
+
+async fn handle_request(
+    req: Request<Incoming>,
+    remote: SocketAddr,
+    event_storage: Arc<EventStorage>,
+    queue: Arc<IngestQueue>,
+    forwarder: Option<Arc<Forwarder>>,
+    project: Option<Arc<str>>,
+) -> Result<Response<Full<Bytes>>, hyper::Error> {
+    if req.uri().path().starts_with("/api/") {
+        return Ok(api::handle_api_request(req, &event_storage, &queue).await);
+    }
+
+    if req.method() == hyper::Method::GET {
+        if let Some(response) = web_ui::handle_ui_request(req.uri().path()) {
+            return Ok(response);
+        }
+    }
+
+    match (req.method(), req.uri().path()) {
+        (&hyper::Method::GET, "/metrics") => Ok(api::metrics_response(&event_storage, &queue)),
+        (&hyper::Method::GET, "/_availability_check") => Ok(Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Full::new(Bytes::from("Not Found")))
+            .unwrap()),
+        (&hyper::Method::POST, "/") => {
+            let server_config = &config().server;
+            let reject = |status: StatusCode, reason: &str| {
+                event_storage.error(
+                    "Access",
+                    &format!("Rejected events from {}: {reason}", remote.ip()),
+                );
+                Ok(api::error_response(status, reason))
+            };
+
+            if forwarder.as_ref().is_some_and(|f| f.is_loop(req.headers())) {
+                return reject(
+                    StatusCode::LOOP_DETECTED,
+                    "request was already forwarded by this instance",
+                );
+            }
+            // The forwarding headers are needed once the body has been read
+            let relay = forwarder.map(|forwarder| (forwarder, req.headers().clone()));
+
+            // Refuse encodings we can't undo before reading the body
+            let encodings = match encoding::parse(req.headers()) {
+                Ok(encodings) => encodings,
+                Err(e) => {
+                    let mut response = reject(e.status(), &e.to_string())?;
+                    response.headers_mut().insert(
+                        ACCEPT_ENCODING,
+                        HeaderValue::from_static(encoding::SUPPORTED),
+                    );
+                    return Ok(response);
+                }
+            };
+
+            // Refuse oversized bodies up front when the client announces the size,
+            // and cap chunked ones while reading
+            let max_body_bytes = server_config.max_body_bytes;
+            let announced = req
+                .headers()
+                .get(CONTENT_LENGTH)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<u64>().ok());
+            if announced.is_some_and(|length| length > max_body_bytes as u64) {
+                return reject(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    &format!("body exceeds {max_body_bytes} bytes"),
+                );
+            }
+
+            let body_bytes = match Limited::new(req.into_body(), max_body_bytes).collect().await {
+                Ok(collected) => collected.to_bytes(),
+                Err(e) if e.is::<LengthLimitError>() => {
+                    return reject(
+                        StatusCode::PAYLOAD_TOO_LARGE,
+                        &format!("body exceeds {max_body_bytes} bytes"),
+                    );
+                }
+                Err(e) => {
+                    return Ok(request_error(
+                        &event_storage,
+                        StatusCode::BAD_REQUEST,
+                        &format!("Failed to read body: {e}"),
+                    ));
+                }
+            };
+
+            let body_bytes = if encodings.is_empty() {
+                body_bytes
+            } else {
+                // Decompressing up to the limit is CPU-bound, keep it off the async workers
+                let max_decoded = server_config.max_decoded_bytes;
+                let decoded = tokio::task::spawn_blocking(move || {
+                    encoding::decode(&encodings, body_bytes, max_decoded)
+                })
+                .await;
+                match decoded {
+                    Ok(Ok(decoded)) => decoded,
+                    Ok(Err(e)) => return reject(e.status(), &e.to_string()),
+                    Err(e) => {
+                        return Ok(request_error(
+                            &event_storage,
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            &format!("Failed to decompress body: {e}"),
+                        ));
+                    }
+                }
+            };
+
+            let ingest =
+                ingest_payloads(body_bytes, remote, &event_storage, &queue, project, relay);
+            Ok(ingest.await)
+        }
+        _ => Ok(Response::builder()
+            .status(StatusCode::METHOD_NOT_ALLOWED)
+            .body(Full::new(Bytes::from("Method Not Allowed")))
+            .unwrap()),
+    }
+}
+
+/// `401` for requests without the `RAY_TOKEN`, when one is set
+///
+/// Everything that reads or changes events needs the token, not just `POST /`. Only
+/// the embedded UI files and the availability check are open, as they hold no data;
+/// the UI passes the token from its own URL on to the API.
+fn require_token(
+    server_config: &ServerConfig,
+    event_storage: &EventStorage,
+    remote: SocketAddr,
+    method: &hyper::Method,
+    uri: &Uri,
+    headers: &HeaderMap,
+) -> Option<Response<Full<Bytes>>> {
+    let path = uri.path();
+    if method == hyper::Method::GET && (web_ui::is_asset(path) || path == "/_availability_check") {
+        return None;
+    }
+    let reason = server_config.check_token(headers, uri.query()).err()?;
+    event_storage.error(
+        "Access",
+        &format!("Rejected {method} {path} from {}: {reason}", remote.ip()),
+    );
+    Some(api::error_response(StatusCode::UNAUTHORIZED, reason))
+}
+
+/// Queue the payloads of a request body and wait for the workers to report on each
+///
+/// Waiting doesn't hold up other requests; only this client waits for its own payloads.
+/// Events are tagged with the listener's `project`. With `relay`, a body that parses
+/// is also handed to the forwarder along with the headers it came with.
+async fn ingest_payloads(
+    body: Bytes,
+    remote: SocketAddr,
+    event_storage: &EventStorage,
+    queue: &Arc<IngestQueue>,
+    project: Option<Arc<str>>,
+    relay: Option<(Arc<Forwarder>, HeaderMap)>,
+) -> Response<Full<Bytes>> {
+    let (reply, mut replies) = tokio::sync::mpsc::unbounded_channel();
+
+    // Each payload is queued as soon as it is parsed, without building the whole batch.
+    // Parsing a large body is CPU-bound too, so it stays off the async workers.
+    let parse_queue = Arc::clone(queue);
+    let parsed = tokio::task::spawn_blocking(move || {
+        let mut results = Vec::new();
+        let outcome = stream_payloads(&body, |p| {
+            let queued = parse_queue.push(p, results.len(), &reply, project.clone());
+            results.push(if queued {
+                PayloadResult::Unknown
+            } else {
+                PayloadResult::QueueFull
+            });
+        });
+        if let (Ok(_), Some((forwarder, headers))) = (&outcome, relay) {
+            forwarder.submit(&body, &headers);
+        }
+        (outcome, results)
+    })
+    .await;
+    let (outcome, mut results) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return request_error(
+                event_storage,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &format!("Failed to parse body: {e}"),
+            )
+        }
+    };
+
+    // Every queued payload holds a sender, so this ends once all of them are reported
+    while let Some((index, status)) = replies.recv().await {
+        results[index] = PayloadResult::Processed(status);
+    }
+
+    let dropped = results
+        .iter()
+        .filter(|result| **result == PayloadResult::QueueFull)
+        .count();
+    if dropped > 0 {
+        event_storage.error(
+            "Request",
+            &format!(
+                "Ingestion queue full, dropped {dropped} of {} payloads from {}",
+                results.len(),
+                remote.ip()
+            ),
+        );
+    }
+
+    let (mut status, mut body) = batch_response(&results);
+    match outcome {
+        Ok(count) => event_storage.info("Request", &format!("Processed {count} payloads")),
+        // Payloads before the error were still queued, and are reported alongside it
+        Err(e) => {
+            let error_msg = if e.is_data() {
+                format!("Invalid payload structure: {e}")
+            } else {
+                format!("Invalid JSON: {e}")
+            };
+            event_storage.error("Request", &error_msg);
+            status = StatusCode::BAD_REQUEST;
+            body["error"] = error_msg.into();
+        }
+    }
+
+    let mut response = api::json_response(status, body);
+    if status == StatusCode::SERVICE_UNAVAILABLE {
+        response
+            .headers_mut()
+            .insert(RETRY_AFTER, HeaderValue::from_static("1"));
+    }
+    response
+}
+
+/// Log a problem with an ingestion request and tell the client
+fn request_error(
+    event_storage: &EventStorage,
+    status: StatusCode,
+    message: &str,
+) -> Response<Full<Bytes>> {
+    event_storage.error("Request", message);
+    api::error_response(status, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::header::AUTHORIZATION;
+
+    #[test]
+    fn test_token_guards_every_route() {
+        let storage = EventStorage::new();
+        storage.set_console_output(false);
+        let server_config = ServerConfig {
+            token: Some("s3cret".to_string()),
+            ..ServerConfig::default()
+        };
+        let remote = SocketAddr::from(([10, 0, 0, 2], 40000));
+        let status = |method: hyper::Method, uri: &str, headers: &HeaderMap| {
+            let uri: Uri = uri.parse().unwrap();
+            require_token(&server_config, &storage, remote, &method, &uri, headers)
+                .map(|response| response.status())
+        };
+        let none = HeaderMap::new();
+        let mut bearer = HeaderMap::new();
+        bearer.insert(AUTHORIZATION, HeaderValue::from_static("Bearer s3cret"));
+
+        for (method, uri) in [
+            (hyper::Method::GET, "/api/events"),
+            (hyper::Method::DELETE, "/api/events"),
+            (hyper::Method::GET, "/api/stream"),
+            (hyper::Method::GET, "/metrics"),
+            (hyper::Method::POST, "/"),
+        ] {
+            assert_eq!(
+                status(method.clone(), uri, &none),
+                Some(StatusCode::UNAUTHORIZED),
+                "{method} {uri}"
+            );
+            assert_eq!(status(method, uri, &bearer), None);
+        }
+        assert_eq!(
+            status(hyper::Method::GET, "/metrics?token=s3cret", &none),
+            None
+        );
+
+        // The page and its files load without it, and pass it on to the API
+        for uri in ["/ui", "/ui/app.js", "/_availability_check"] {
+            assert_eq!(status(hyper::Method::GET, uri, &none), None);
+        }
+    }
+}
@@ -0,0 +1,351 @@
+// server/queue.rs - bounded queue between request handlers and the workers that store payloads
+use crate::event_storage::{EventStorage, PayloadStatus};
+use serde_json::Value;
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Condvar, Mutex};
+use tokio::runtime::Handle;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Where workers report the status of a payload, along with its position in the request
+pub type Reply = UnboundedSender<(usize, PayloadStatus)>;
+
+struct Job {
+    payload: Value,
+    index: usize,
+    reply: Reply,
+    /// Project of the listener the payload came in on
+    project: Option<Arc<str>>,
+}
+
+/// Queue counters, as reported by `/api/stats`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QueueStats {
+    /// Payloads waiting for a worker
+    pub depth: usize,
+    pub capacity: usize,
+    pub workers: usize,
+    /// Payloads accepted since startup
+    pub queued: u64,
+    /// Payloads refused because the queue was full
+    pub dropped: u64,
+}
+
+/// Accepts payloads without waiting for them to be processed
+///
+/// Workers take batches in arrival order, process them in parallel and store them
+/// one batch at a time in the order they were taken, so events keep their order.
+/// The workers stop once the queue is dropped, after storing what is still queued.
+/// When started from within a tokio runtime, the workers enter it, so sinks like the
+/// webhook can spawn their requests on it.
+pub struct IngestQueue {
+    sender: SyncSender<Job>,
+    shared: Arc<Shared>,
+    capacity: usize,
+    workers: usize,
+    queued: AtomicU64,
+    dropped: AtomicU64,
+}
+
+struct Shared {
+    /// The receiver, and the ticket handed to the next batch taken from it
+    receiver: Mutex<(Receiver<Job>, u64)>,
+    /// Ticket of the batch whose turn it is to be stored
+    next_store: Mutex<u64>,
+    turn: Condvar,
+    depth: AtomicUsize,
+}
+
+impl IngestQueue {
+    pub fn start(
+        storage: Arc<EventStorage>,
+        capacity: usize,
+        workers: usize,
+        batch_size: usize,
+    ) -> std::io::Result<Self> {
+        let runtime = Handle::try_current().ok();
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        let shared = Arc::new(Shared {
+            receiver: Mutex::new((receiver, 0)),
+            next_store: Mutex::new(0),
+            turn: Condvar::new(),
+            depth: AtomicUsize::new(0),
+        });
+
+        for index in 0..workers {
+            let shared = Arc::clone(&shared);
+            let storage = Arc::clone(&storage);
+            let runtime = runtime.clone();
+            std::thread::Builder::new()
+                .name(format!("ray-ingest-{index}"))
+                .spawn(move || {
+                    let _runtime = runtime.as_ref().map(Handle::enter);
+                    work(&shared, &storage, batch_size)
+                })?;
+        }
+
+        Ok(Self {
+            sender,
+            shared,
+            capacity,
+            workers,
+            queued: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+        })
+    }
+
+    /// Queue a payload, or return `false` if the queue is full and it was dropped
+    ///
+    /// Once the payload is stored, its status is sent to `reply` together with `index`.
+    /// Its events are tagged with `project`, if any.
+    pub fn push(
+        &self,
+        payload: Value,
+        index: usize,
+        reply: &Reply,
+        project: Option<Arc<str>>,
+    ) -> bool {
+        let job = Job {
+            payload,
+            index,
+            reply: reply.clone(),
+            project,
+        };
+        // Counted before sending so a fast worker can't take the depth below zero
+        self.shared.depth.fetch_add(1, Ordering::Relaxed);
+        match self.sender.try_send(job) {
+            Ok(()) => {
+                self.queued.fetch_add(1, Ordering::Relaxed);
+                true
+            }
+            Err(TrySendError::Full(_) | TrySendError::Disconnected(_)) => {
+                self.shared.depth.fetch_sub(1, Ordering::Relaxed);
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                false
+            }
+        }
+    }
+
+    pub fn stats(&self) -> QueueStats {
+        QueueStats {
+            depth: self.shared.depth.load(Ordering::Relaxed),
+            capacity: self.capacity,
+            workers: self.workers,
+            queued: self.queued.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+fn work(shared: &Shared, storage: &EventStorage, batch_size: usize) {
+    loop {
+        let (batch, ticket) = {
+            let mut receiver = shared.receiver.lock().unwrap();
+            // Fails once every sender is gone and the queue is empty
+            let Ok(first) = receiver.0.recv() else {
+                return;
+            };
+            let mut batch = vec![first];
+            while batch.len() < batch_size {
+                match receiver.0.try_recv() {
+                    Ok(job) => batch.push(job),
+                    Err(_) => break,
+                }
+            }
+            let ticket = receiver.1;
+            receiver.1 += 1;
+            (batch, ticket)
+        };
+        shared.depth.fetch_sub(batch.len(), Ordering::Relaxed);
+
+        let mut entries = Vec::with_capacity(batch.len());
+        let mut replies = Vec::with_capacity(batch.len());
+        for job in batch {
+            let project = job.project.as_deref();
+            // A panic must not take the worker down with its ticket, or every later batch waits forever.
+            // Release builds abort on panic instead, so processors must not panic on bad input.
+            let (prepared, status) = panic::catch_unwind(AssertUnwindSafe(|| {
+                storage.prepare_payload(&job.payload, project)
+            }))
+            .unwrap_or_else(|panic| {
+                storage.payload_panicked(&job.payload, project, panic_message(&*panic))
+            });
+            entries.extend(prepared);
+            replies.push((job.index, job.reply, status));
+        }
+
+        // Wait for the batches taken earlier, then store this one
+        let mut next_store = shared
+            .turn
+            .wait_while(shared.next_store.lock().unwrap(), |next| *next != ticket)
+            .unwrap();
+        let stored = panic::catch_unwind(AssertUnwindSafe(|| storage.store_entries(entries)));
+        if let Err(panic) = stored {
+            storage.error(
+                "Ingest",
+                &format!("Storing a batch panicked: {}", panic_message(&*panic)),
+            );
+        }
+        *next_store += 1;
+        shared.turn.notify_all();
+        drop(next_store);
+
+        for (index, reply, status) in replies {
+            // The client may have gone away; the payload is stored either way
+            let _ = reply.send((index, status));
+        }
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown panic")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::EventProcessor;
+    use serde_json::json;
+
+    #[test]
+    fn test_queue_keeps_order_and_drops_when_full() {
+        let storage = Arc::new(EventStorage::new());
+        storage.set_console_output(false);
+        let queue = IngestQueue::start(Arc::clone(&storage), 1000, 4, 7).unwrap();
+        let (reply, mut replies) = tokio::sync::mpsc::unbounded_channel();
+
+        for index in 0..500 {
+            let payload = json!({"type": "log", "content": {"values": [index]}});
+            assert!(queue.push(payload, index, &reply, None));
+        }
+        assert!(queue.push(json!({"type": "mystery"}), 500, &reply, None));
+        drop(reply);
+
+        // Every payload is reported once it is stored
+        let mut statuses = vec![None; 501];
+        while let Some((index, status)) = replies.blocking_recv() {
+            statuses[index] = Some(status);
+        }
+        assert!(statuses[..500]
+            .iter()
+            .all(|status| *status == Some(PayloadStatus::Accepted)));
+        assert_eq!(
+            statuses[500],
+            Some(PayloadStatus::UnknownType("mystery".to_string()))
+        );
+
+        // Oldest first, in the order they were queued
+        let values: Vec<i64> = storage
+            .get_events_optimized()
+            .iter()
+            .rev()
+            .take(500)
+            .map(|entry| entry.raw_payload["content"]["values"][0].as_i64().unwrap())
+            .collect();
+        assert_eq!(values, (0..500).collect::<Vec<_>>());
+        assert_eq!(queue.stats().queued, 501);
+        assert_eq!(queue.stats().depth, 0);
+
+        // Without workers nothing is taken off the queue
+        let stalled = IngestQueue::start(Arc::clone(&storage), 2, 0, 1).unwrap();
+        let (reply, _replies) = tokio::sync::mpsc::unbounded_channel();
+        assert!(stalled.push(json!({"type": "log"}), 0, &reply, None));
+        assert!(stalled.push(json!({"type": "log"}), 1, &reply, None));
+        assert!(!stalled.push(json!({"type": "log"}), 2, &reply, None));
+        let stats = stalled.stats();
+        assert_eq!((stats.depth, stats.queued, stats.dropped), (2, 2, 1));
+    }
+
+    #[test]
+    fn test_panicking_payload_does_not_stall_workers() {
+        let storage = Arc::new(EventStorage::new());
+        storage.set_console_output(false);
+        let queue = IngestQueue::start(Arc::clone(&storage), 100, 4, 1).unwrap();
+        let (reply, mut replies) = tokio::sync::mpsc::unbounded_channel();
+
+        crate::events::registry().register_processor(
+            "test_panic",
+            EventProcessor::new(|_| panic!("processor bug")),
+        );
+        assert!(queue.push(json!({"type": "test_panic"}), 0, &reply, None));
+        for index in 1..20 {
+            assert!(queue.push(json!({"type": "log"}), index, &reply, None));
+        }
+        drop(reply);
+
+        let mut statuses = vec![None; 20];
+        while let Some((index, status)) = replies.blocking_recv() {
+            statuses[index] = Some(status);
+        }
+        assert!(matches!(
+            &statuses[0],
+            Some(PayloadStatus::Failed(reason)) if reason.starts_with("Processing panicked")
+        ));
+        assert!(statuses[1..]
+            .iter()
+            .all(|status| *status == Some(PayloadStatus::Accepted)));
+        assert_eq!(
+            storage.get_events_optimized().last().unwrap().label,
+            "Ingestion Error"
+        );
+    }
+
+    #[test]
+    fn test_workers_reach_webhook_sink() {
+        use crate::notifications::sinks::WebhookSink;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let listener = runtime
+            .block_on(tokio::net::TcpListener::bind("127.0.0.1:0"))
+            .unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let received = runtime.spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 4096];
+            while !String::from_utf8_lossy(&request).contains("Deploy done") {
+                let read = stream.read(&mut buffer).await.unwrap();
+                assert!(read > 0, "webhook request ended early");
+                request.extend_from_slice(&buffer[..read]);
+            }
+            let _ = stream
+                .write_all(b"HTTP/1.1 204 No Content\r\ncontent-length: 0\r\n\r\n")
+                .await;
+            String::from_utf8_lossy(&request).into_owned()
+        });
+
+        let storage = Arc::new(EventStorage::new());
+        storage.set_console_output(false);
+        storage
+            .notifications()
+            .add_sink(Box::new(WebhookSink::new(&url).unwrap()));
+        let queue = {
+            let _runtime = runtime.enter();
+            IngestQueue::start(Arc::clone(&storage), 10, 1, 1).unwrap()
+        };
+
+        let (reply, mut replies) = tokio::sync::mpsc::unbounded_channel();
+        let payload = json!({"type": "notify", "content": {"value": "Deploy done"}});
+        assert!(queue.push(payload, 0, &reply, None));
+        drop(reply);
+        assert!(replies.blocking_recv().is_some());
+
+        let timeout = std::time::Duration::from_secs(5);
+        let request = runtime
+            .block_on(async { tokio::time::timeout(timeout, received).await })
+            .expect("webhook was not called")
+            .unwrap();
+        assert!(request.starts_with("POST /hook"));
+        assert!(storage
+            .internal_log()
+            .iter()
+            .all(|record| !record.message.contains("webhook sink failed")));
+    }
+}
@@ -0,0 +1,336 @@
+// server/stream.rs - live event push over Server-Sent Events and WebSocket
+use super::ServerBody;
+use crate::event_filter::EventFilter;
+use crate::event_storage::EventStorage;
+use crate::events::EventEntry;
+use bytes::Bytes;
+use futures_util::{SinkExt, StreamExt};
+use http_body_util::{BodyExt, Empty, Full};
+use hyper::body::{Body, Frame, Incoming};
+use hyper::header::{self, HeaderValue};
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+/// Messages queued per SSE client before we stop pulling from the feed
+const CLIENT_BUFFER: usize = 64;
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// `GET /api/stream[?filter=...]` - WebSocket when the client asks to upgrade, SSE otherwise
+///
+/// `shutdown` turns true when the server stops, which ends the stream.
+pub fn handle_stream(
+    req: Request<Incoming>,
+    event_storage: &Arc<EventStorage>,
+    shutdown: watch::Receiver<bool>,
+) -> Response<ServerBody> {
+    let filter = match stream_filter(&req) {
+        Ok(filter) => filter,
+        Err(e) => return text_response(StatusCode::BAD_REQUEST, e),
+    };
+
+    let wants_websocket = req
+        .headers()
+        .get(header::UPGRADE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+
+    if wants_websocket {
+        handle_websocket(req, filter, event_storage, shutdown)
+    } else {
+        handle_sse(filter, event_storage, shutdown)
+    }
+}
+
+fn stream_filter(req: &Request<Incoming>) -> Result<EventFilter, String> {
+    let expression = req
+        .uri()
+        .query()
+        .and_then(|query| {
+            form_urlencoded::parse(query.as_bytes())
+                .find(|(key, _)| key == "filter")
+                .map(|(_, value)| value.into_owned())
+        })
+        .unwrap_or_default();
+    EventFilter::parse(&expression)
+}
+
+/// What a subscriber should send next
+enum StreamMessage {
+    Event(Value),
+    /// The subscriber fell behind and this many entries were skipped
+    Lagged(u64),
+}
+
+/// Wait for the next entry that passes the filter, or `None` once storage is gone
+async fn next_message(
+    events: &mut broadcast::Receiver<Arc<EventEntry>>,
+    filter: &EventFilter,
+) -> Option<StreamMessage> {
+    loop {
+        match events.recv().await {
+            Ok(entry) if filter.matches(&entry) => {
+                return Some(StreamMessage::Event(entry.to_json()))
+            }
+            Ok(_) => {}
+            Err(RecvError::Lagged(dropped)) => return Some(StreamMessage::Lagged(dropped)),
+            Err(RecvError::Closed) => return None,
+        }
+    }
+}
+
+fn handle_sse(
+    filter: EventFilter,
+    event_storage: &Arc<EventStorage>,
+    mut shutdown: watch::Receiver<bool>,
+) -> Response<ServerBody> {
+    let mut events = event_storage.subscribe();
+    let storage = Arc::clone(event_storage);
+    let (tx, rx) = mpsc::channel(CLIENT_BUFFER);
+
+    tokio::spawn(async move {
+        let mut keep_alive = tokio::time::interval(KEEP_ALIVE_INTERVAL);
+        loop {
+            let chunk = tokio::select! {
+                message = next_message(&mut events, &filter) => match message {
+                    Some(StreamMessage::Event(event)) => sse_chunk("event", &event),
+                    Some(StreamMessage::Lagged(dropped)) => {
+                        storage.warn("Stream", &format!("SSE client lagged, skipped {dropped} events"));
+                        sse_chunk("lagged", &json!({ "dropped": dropped }))
+                    }
+                    None => break,
+                },
+                _ = keep_alive.tick() => Bytes::from_static(b": keep-alive\n\n"),
+                // Ending the body lets the connection close with the server
+                _ = shutdown.changed() => break,
+            };
+
+            // While the client's buffer is full we stop receiving, so a slow
+            // client lags behind the feed instead of holding up ingestion
+            if tx.send(chunk).await.is_err() {
+                break; // Client went away
+            }
+        }
+    });
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, "text/event-stream")
+        .header(header::CACHE_CONTROL, "no-cache")
+        .body(ChannelBody { rx }.boxed())
+        .unwrap()
+}
+
+fn sse_chunk(event: &str, data: &Value) -> Bytes {
+    Bytes::from(format!("event: {event}\ndata: {data}\n\n"))
+}
+
+fn handle_websocket(
+    mut req: Request<Incoming>,
+    filter: EventFilter,
+    event_storage: &Arc<EventStorage>,
+    mut shutdown: watch::Receiver<bool>,
+) -> Response<ServerBody> {
+    if let Err((status, reason)) = check_upgrade(&req) {
+        event_storage.warn("Stream", &format!("Rejected WebSocket upgrade: {reason}"));
+        let mut response = text_response(status, reason);
+        if status == StatusCode::UPGRADE_REQUIRED {
+            response.headers_mut().insert(
+                header::SEC_WEBSOCKET_VERSION,
+                HeaderValue::from_static("13"),
+            );
+        }
+        return response;
+    }
+    let Some(key) = req.headers().get(header::SEC_WEBSOCKET_KEY) else {
+        return text_response(
+            StatusCode::BAD_REQUEST,
+            "Missing Sec-WebSocket-Key".to_string(),
+        );
+    };
+    let accept = derive_accept_key(key.as_bytes());
+
+    let mut events = event_storage.subscribe();
+    let storage = Arc::clone(event_storage);
+    let on_upgrade = hyper::upgrade::on(&mut req);
+
+    tokio::spawn(async move {
+        let upgraded = match on_upgrade.await {
+            Ok(upgraded) => upgraded,
+            Err(e) => {
+                storage.error("Stream", &format!("WebSocket upgrade failed: {e}"));
+                return;
+            }
+        };
+        let socket =
+            WebSocketStream::from_raw_socket(TokioIo::new(upgraded), Role::Server, None).await;
+        let (mut outgoing, mut incoming) = socket.split();
+
+        loop {
+            tokio::select! {
+                message = next_message(&mut events, &filter) => {
+                    let payload = match message {
+                        Some(StreamMessage::Event(event)) => json!({ "type": "event", "event": event }),
+                        Some(StreamMessage::Lagged(dropped)) => {
+                            storage.warn("Stream", &format!("WebSocket client lagged, skipped {dropped} events"));
+                            json!({ "type": "lagged", "dropped": dropped })
+                        }
+                        None => break,
+                    };
+                    // Sending waits for the socket, so a slow client lags like an SSE one
+                    if outgoing.send(Message::text(payload.to_string())).await.is_err() {
+                        break;
+                    }
+                }
+                frame = incoming.next() => match frame {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    // Pings are answered by tungstenite; anything else is ignored
+                    Some(Ok(_)) => {}
+                },
+                _ = shutdown.changed() => {
+                    let _ = outgoing.send(Message::Close(None)).await;
+                    break;
+                }
+            }
+        }
+    });
+
+    let mut response = Response::new(Empty::new().boxed());
+    *response.status_mut() = StatusCode::SWITCHING_PROTOCOLS;
+    let headers = response.headers_mut();
+    headers.insert(header::UPGRADE, HeaderValue::from_static("websocket"));
+    headers.insert(header::CONNECTION, HeaderValue::from_static("Upgrade"));
+    headers.insert(
+        header::SEC_WEBSOCKET_ACCEPT,
+        HeaderValue::from_str(&accept).unwrap(),
+    );
+    response
+}
+
+/// Refuse upgrades that aren't a version 13 `GET`, or that a page on another site started
+///
+/// Browsers let any page open a WebSocket to localhost and always send its `Origin`,
+/// so it has to match the host the request was sent to. Clients outside a browser
+/// send no `Origin` and are left to the token check.
+fn check_upgrade<B>(req: &Request<B>) -> Result<(), (StatusCode, String)> {
+    if req.method() != hyper::Method::GET {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("{} instead of GET", req.method()),
+        ));
+    }
+
+    let headers = req.headers();
+    if headers
+        .get(header::SEC_WEBSOCKET_VERSION)
+        .is_none_or(|version| version != "13")
+    {
+        return Err((
+            StatusCode::UPGRADE_REQUIRED,
+            "Only WebSocket version 13 is supported".to_string(),
+        ));
+    }
+
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return Ok(());
+    };
+    let host = headers
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .or_else(|| req.uri().authority().map(|authority| authority.as_str()));
+    let origin = origin.to_str().unwrap_or_default();
+    let authority = origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"));
+    match authority.zip(host) {
+        Some((authority, host)) if authority.eq_ignore_ascii_case(host) => Ok(()),
+        _ => Err((
+            StatusCode::FORBIDDEN,
+            format!("Cross-origin request from {origin}"),
+        )),
+    }
+}
+
+fn text_response(status: StatusCode, message: String) -> Response<ServerBody> {
+    Response::builder()
+        .status(status)
+        .body(Full::new(Bytes::from(message)).boxed())
+        .unwrap()
+}
+
+/// Response body fed by the task that follows the event feed
+struct ChannelBody {
+    rx: mpsc::Receiver<Bytes>,
+}
+
+impl Body for ChannelBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+        self.rx
+            .poll_recv(cx)
+            .map(|chunk| chunk.map(|chunk| Ok(Frame::data(chunk))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upgrade(method: &str, headers: &[(&str, &str)]) -> Result<(), StatusCode> {
+        let mut req = Request::builder().method(method).uri("/api/stream");
+        for (name, value) in headers {
+            req = req.header(*name, *value);
+        }
+        check_upgrade(&req.body(()).unwrap()).map_err(|(status, _)| status)
+    }
+
+    #[test]
+    fn test_websocket_upgrade_checks() {
+        let version = ("sec-websocket-version", "13");
+        let host = ("host", "127.0.0.1:23517");
+
+        // The web UI, and clients outside a browser that send no Origin
+        assert_eq!(
+            upgrade(
+                "GET",
+                &[version, host, ("origin", "http://127.0.0.1:23517")]
+            ),
+            Ok(())
+        );
+        assert_eq!(upgrade("GET", &[version, host]), Ok(()));
+
+        // Any other page the developer has open
+        for origin in ["https://evil.example", "http://127.0.0.1:8080", "null"] {
+            assert_eq!(
+                upgrade("GET", &[version, host, ("origin", origin)]),
+                Err(StatusCode::FORBIDDEN),
+                "{origin}"
+            );
+        }
+
+        assert_eq!(
+            upgrade("POST", &[version, host]),
+            Err(StatusCode::BAD_REQUEST)
+        );
+        assert_eq!(upgrade("GET", &[host]), Err(StatusCode::UPGRADE_REQUIRED));
+        assert_eq!(
+            upgrade("GET", &[("sec-websocket-version", "8"), host]),
+            Err(StatusCode::UPGRADE_REQUIRED)
+        );
+    }
+}
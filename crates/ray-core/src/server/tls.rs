@@ -0,0 +1,174 @@
+// server/tls.rs - optional TLS termination for the HTTP listener
+use crate::config::env_flag;
+use anyhow::{anyhow, Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::{crypto, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+/// Certificate settings; the server speaks plain HTTP unless this is configured
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+    /// Generate a self-signed certificate at the paths above if they don't exist yet
+    pub self_signed: bool,
+    /// Names and addresses the generated certificate is valid for
+    pub hosts: Vec<String>,
+}
+
+impl TlsConfig {
+    /// Read `RAY_TLS_CERT`, `RAY_TLS_KEY`, `RAY_TLS_SELF_SIGNED` and `RAY_TLS_HOSTS`
+    ///
+    /// A certificate and key enable TLS. `RAY_TLS_SELF_SIGNED` alone also does,
+    /// keeping the generated files in `~/.config/ray/tls` between runs.
+    pub fn from_env() -> Option<Self> {
+        let path = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from)
+        };
+        let self_signed = env_flag("RAY_TLS_SELF_SIGNED").unwrap_or(false);
+
+        let (cert_path, key_path) = match (path("RAY_TLS_CERT"), path("RAY_TLS_KEY")) {
+            (Some(cert), Some(key)) => (cert, key),
+            _ if self_signed => {
+                let dir = default_dir()?;
+                (dir.join("cert.pem"), dir.join("key.pem"))
+            }
+            _ => return None,
+        };
+
+        let mut hosts = vec!["localhost".to_string(), "127.0.0.1".to_string()];
+        if let Ok(raw) = std::env::var("RAY_TLS_HOSTS") {
+            hosts.extend(
+                raw.split(',')
+                    .map(str::trim)
+                    .filter(|host| !host.is_empty())
+                    .map(str::to_string),
+            );
+        }
+
+        Some(Self {
+            cert_path,
+            key_path,
+            self_signed,
+            hosts,
+        })
+    }
+
+    /// Load the certificate, generating it first when asked to and it's missing
+    ///
+    /// Returns the acceptor and, if one was just generated, a message saying where.
+    /// With `http2`, clients can negotiate it through ALPN.
+    pub fn acceptor(&self, http2: bool) -> Result<(TlsAcceptor, Option<String>)> {
+        let mut generated = None;
+        if self.self_signed && !(self.cert_path.exists() && self.key_path.exists()) {
+            self.generate()?;
+            generated = Some(format!(
+                "Generated a self-signed certificate for {} at {}",
+                self.hosts.join(", "),
+                self.cert_path.display()
+            ));
+        }
+
+        let certs = CertificateDer::pem_file_iter(&self.cert_path)
+            .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| anyhow!("Could not read {}: {e}", self.cert_path.display()))?;
+        let key = PrivateKeyDer::from_pem_file(&self.key_path)
+            .map_err(|e| anyhow!("Could not read {}: {e}", self.key_path.display()))?;
+
+        // Pick the provider explicitly so it doesn't depend on crate features elsewhere
+        let mut config =
+            ServerConfig::builder_with_provider(Arc::new(crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()?
+                .with_no_client_auth()
+                .with_single_cert(certs, key)
+                .context("Invalid TLS certificate or key")?;
+        config.alpn_protocols = if http2 {
+            vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+        } else {
+            vec![b"http/1.1".to_vec()]
+        };
+
+        Ok((TlsAcceptor::from(Arc::new(config)), generated))
+    }
+
+    fn generate(&self) -> Result<()> {
+        let certified = rcgen::generate_simple_self_signed(self.hosts.clone())
+            .context("Could not generate a self-signed certificate")?;
+
+        for path in [&self.cert_path, &self.key_path] {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)
+                    .with_context(|| format!("Could not create {}", dir.display()))?;
+            }
+        }
+        std::fs::write(&self.cert_path, certified.cert.pem())
+            .with_context(|| format!("Could not write {}", self.cert_path.display()))?;
+        write_private(&self.key_path, &certified.key_pair.serialize_pem())
+    }
+}
+
+/// `$XDG_CONFIG_HOME/ray/tls`, falling back to `~/.config/ray/tls`
+fn default_dir() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join("ray").join("tls"))
+}
+
+/// Write the key so only the current user can read it
+fn write_private(path: &Path, contents: &str) -> Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .with_context(|| format!("Could not write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_signed_certificate_is_generated_once() {
+        let dir = std::env::temp_dir().join(format!("ray-tls-{}", std::process::id()));
+        let config = TlsConfig {
+            cert_path: dir.join("cert.pem"),
+            key_path: dir.join("key.pem"),
+            self_signed: true,
+            hosts: vec!["localhost".to_string(), "127.0.0.1".to_string()],
+        };
+
+        let (_, generated) = config.acceptor(true).unwrap();
+        assert!(generated.is_some());
+        let cert = std::fs::read(&config.cert_path).unwrap();
+
+        // The second run reuses the files, so clients can keep trusting the certificate
+        let (_, generated) = config.acceptor(true).unwrap();
+        assert!(generated.is_none());
+        assert_eq!(std::fs::read(&config.cert_path).unwrap(), cert);
+
+        let missing = TlsConfig {
+            self_signed: false,
+            cert_path: dir.join("missing.pem"),
+            ..config
+        };
+        assert!(missing.acceptor(true).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,438 @@
+// Browser front-end for the embedded server: list, filters and details
+// backed by /api/events, kept live through the /api/stream SSE feed.
+"use strict";
+
+const MAX_EVENTS = 5000;
+// Path fragments of vendor frames, from RAY_VENDOR_PATTERNS via /api/types
+let VENDOR_PATTERNS = ["/vendor/", "/node_modules/"];
+
+// Loaded from /api/types: the built-in types plus any registered on the server,
+// each with the payload type names it covers
+let EVENT_TYPES = [];
+
+const state = {
+  events: [], // newest first
+  enabledTypes: new Set(),
+  search: "",
+  selectedId: null,
+};
+
+const $ = (id) => document.getElementById(id);
+
+// With RAY_TOKEN set, open the page as /ui?token=... and every API request carries it along
+const TOKEN = new URLSearchParams(location.search).get("token");
+function api(path) {
+  if (!TOKEN) return path;
+  return `${path}${path.includes("?") ? "&" : "?"}token=${encodeURIComponent(TOKEN)}`;
+}
+
+// Small DOM builder; strings become text nodes so payload content is never parsed as HTML
+function el(tag, attrs, ...children) {
+  const node = document.createElement(tag);
+  for (const [key, value] of Object.entries(attrs || {})) {
+    if (key.startsWith("on")) node.addEventListener(key.slice(2), value);
+    else if (value !== undefined && value !== null && value !== false) node.setAttribute(key, value);
+  }
+  for (const child of children.flat()) {
+    if (child === null || child === undefined || child === false) continue;
+    node.append(child instanceof Node ? child : String(child));
+  }
+  return node;
+}
+
+function typeOf(event) {
+  const type = EVENT_TYPES.find((t) => t.aliases.includes(event.event_type));
+  return type ? type.name : null;
+}
+
+// Same lookup order as resolve_field in src/events/base.rs: root, content, content.values
+function resolveField(payload, path) {
+  const lookup = (root) =>
+    path.split(".").reduce((value, segment) => (value == null ? undefined : value[segment]), root);
+  for (const root of [payload, payload.content, payload.content && payload.content.values]) {
+    const value = root == null ? undefined : lookup(root);
+    if (value !== undefined) return value;
+  }
+  return undefined;
+}
+
+function matchesFilter(event) {
+  const type = typeOf(event);
+  if (!type || !state.enabledTypes.has(type)) return false;
+  if (!state.search) return true;
+  const needle = state.search.toLowerCase();
+  return (
+    event.label.toLowerCase().includes(needle) ||
+    event.description.toLowerCase().includes(needle) ||
+    JSON.stringify(event.payload).toLowerCase().includes(needle)
+  );
+}
+
+function visibleEvents() {
+  return state.events.filter(matchesFilter);
+}
+
+// ---- list panel ----
+
+function renderTypeFilters() {
+  const container = $("type-filters");
+  container.replaceChildren(
+    ...EVENT_TYPES.map((type) =>
+      el(
+        "button",
+        {
+          class: "chip" + (state.enabledTypes.has(type.name) ? " active" : ""),
+          onclick: () => {
+            if (state.enabledTypes.has(type.name)) state.enabledTypes.delete(type.name);
+            else state.enabledTypes.add(type.name);
+            renderTypeFilters();
+            renderList();
+          },
+        },
+        type.label
+      )
+    )
+  );
+}
+
+function renderList() {
+  const events = visibleEvents();
+  if (!events.some((e) => e.id === state.selectedId)) {
+    state.selectedId = events.length ? events[0].id : null;
+  }
+
+  $("events").replaceChildren(
+    ...events.map((event) =>
+      el(
+        "li",
+        {
+          class: event.id === state.selectedId ? "selected" : "",
+          onclick: () => select(event.id),
+        },
+        el("div", { class: "label" }, event.label),
+        el("div", { class: "description" }, event.description),
+        el("div", { class: "muted" }, event.timestamp)
+      )
+    )
+  );
+  $("empty").hidden = events.length > 0;
+  renderDetails();
+}
+
+function select(id) {
+  state.selectedId = id;
+  renderList();
+  const row = $("events").querySelector("li.selected");
+  if (row) row.scrollIntoView({ block: "nearest" });
+}
+
+document.addEventListener("keydown", (e) => {
+  if (e.target === $("search") || (e.key !== "ArrowUp" && e.key !== "ArrowDown")) return;
+  const events = visibleEvents();
+  const index = events.findIndex((ev) => ev.id === state.selectedId);
+  const next = events[index + (e.key === "ArrowDown" ? 1 : -1)];
+  if (next) {
+    e.preventDefault();
+    select(next.id);
+  }
+});
+
+// ---- details panel ----
+
+function renderDetails() {
+  const event = state.events.find((e) => e.id === state.selectedId);
+  if (!event) {
+    $("details").replaceChildren(el("div", { class: "empty" }, "Select an event to view details"));
+    return;
+  }
+
+  const type = EVENT_TYPES.find((t) => t.name === typeOf(event));
+  const renderer = RENDERERS[typeOf(event)] || (type && type.fields.length ? renderFields(type) : () => []);
+  $("details").replaceChildren(
+    el(
+      "div",
+      {},
+      el(
+        "div",
+        { class: "details-header" },
+        el("h2", {}, event.label),
+        el("span", { class: "muted" }, event.timestamp)
+      ),
+      renderer(event.payload.content || {}, event),
+      renderOrigin(event.payload.origin),
+      section(
+        "Raw payload",
+        el(
+          "button",
+          { class: "link", onclick: () => navigator.clipboard.writeText(JSON.stringify(event.payload, null, 2)) },
+          "copy raw payload"
+        ),
+        code(event.payload)
+      )
+    )
+  );
+}
+
+function section(title, ...children) {
+  return el("section", {}, el("h3", {}, title), ...children);
+}
+
+// Values masked at ingestion contain this marker
+const REDACTED = "[redacted]";
+
+function redactedClass(text) {
+  return String(text).includes(REDACTED) ? " redacted" : "";
+}
+
+function code(value) {
+  const text = typeof value === "string" ? value : JSON.stringify(value, null, 2);
+  return el("pre", { class: "mono" + redactedClass(text) }, text);
+}
+
+function fields(rows) {
+  const present = rows.filter(([, value]) => value !== undefined && value !== null && value !== "");
+  if (!present.length) return null;
+  return el(
+    "table",
+    { class: "fields" },
+    present.map(([name, value]) => {
+      const text = typeof value === "object" && !(value instanceof Node) ? JSON.stringify(value) : value;
+      return el("tr", {}, el("td", {}, name), el("td", { class: "mono" + (value instanceof Node ? "" : redactedClass(text)) }, text));
+    })
+  );
+}
+
+function badge(text, kind) {
+  return el("span", { class: "badge " + kind }, text);
+}
+
+function levelBadge(level) {
+  const normalized = String(level || "info").toLowerCase();
+  const kind = ["error", "critical", "alert", "emergency"].includes(normalized)
+    ? "error"
+    : normalized === "warning"
+      ? "warning"
+      : "info";
+  return badge(String(level || "Info").toUpperCase(), kind);
+}
+
+function renderOrigin(origin) {
+  if (!origin || !origin.file) return null;
+  return section(
+    "Origin",
+    fields([
+      ["File", `${origin.file}:${origin.line_number ?? origin.line ?? 0}`],
+      ["Host", origin.hostname],
+    ])
+  );
+}
+
+// Declarative custom types: the field mappings from the server's custom types file
+function renderFields(type) {
+  return (content, event) => [
+    event.description && section("Summary", code(event.description)),
+    section(
+      "Fields",
+      fields(type.fields.map((field) => [field.label, resolveField(event.payload, field.path)]))
+    ),
+  ];
+}
+
+const RENDERERS = {
+  log(content) {
+    const values = content.values;
+    let message = "";
+    let context = null;
+    let level = "Info";
+    if (Array.isArray(values)) {
+      message = typeof values[0] === "string" ? values[0] : JSON.stringify(values[0], null, 2);
+      if (values.length > 1) context = values.slice(1);
+    } else if (values && typeof values === "object") {
+      level = values.level || level;
+      message = values.message || "";
+      context = values.context || null;
+    }
+    return [
+      section("Message", el("div", {}, levelBadge(level)), code(message ?? "")),
+      context && section("Context", code(context)),
+    ];
+  },
+
+  application_log(content) {
+    return [
+      section(
+        "Message",
+        el("div", {}, levelBadge(content.level), content.channel ? el("span", { class: "muted" }, " " + content.channel) : null),
+        code(content.value ?? "")
+      ),
+      content.context && section("Context", code(content.context)),
+    ];
+  },
+
+  query(content) {
+    return [
+      section("SQL", code(content.sql || "")),
+      section(
+        "Details",
+        fields([
+          ["Duration", content.time !== undefined ? `${content.time} ms` : null],
+          ["Connection", content.connection_name],
+          ["Affected rows", content.affected_rows],
+        ])
+      ),
+      Array.isArray(content.bindings) && content.bindings.length > 0 && section("Bindings", code(content.bindings)),
+    ];
+  },
+
+  cache(content) {
+    const values = content.values || {};
+    const operation = values.Event || "Unknown";
+    const kind = operation === "Hit" ? "success" : operation === "Missed" ? "error" : "info";
+    return [
+      section(
+        "Operation",
+        el("div", {}, badge(operation, kind)),
+        fields([
+          ["Key", values.Key],
+          ["Store", values.Store],
+          ["TTL", values.TTL],
+          ["Expiration", values["Expiration in seconds"] !== undefined ? `${values["Expiration in seconds"]}s` : null],
+          ["Tags", values.Tags],
+        ])
+      ),
+      values.Value !== undefined && section("Value", code(values.Value)),
+    ];
+  },
+
+  http(content) {
+    const values = content.values || {};
+    const status = values.Status;
+    const kind = status === undefined ? "info" : status >= 400 ? "error" : status >= 300 ? "warning" : "success";
+    const body = values.Data ?? values.Body;
+    return [
+      section(
+        values.Type || "Request",
+        el("div", {}, values.Method ? badge(values.Method, "info") : null, " ", status !== undefined ? badge(status, kind) : null),
+        fields([
+          ["URL", values.URL],
+          ["Duration", values.Duration !== undefined ? `${values.Duration}s` : null],
+          ["Connection time", values["Connection time"] !== undefined ? `${values["Connection time"]}s` : null],
+          ["Size", values.Size !== undefined ? `${values.Size} bytes` : null],
+          ["Request size", values["Request Size"] !== undefined ? `${values["Request Size"]} bytes` : null],
+        ])
+      ),
+      values.Headers && section("Headers", fields(Object.entries(values.Headers))),
+      body !== undefined && section("Body", code(body)),
+    ];
+  },
+
+  exception(content) {
+    const frames = (content.frames || []).map((frame) => ({
+      file: frame.file_name ?? frame.file ?? "",
+      line: frame.line_number ?? frame.line ?? 0,
+      method: frame.method ?? frame.function ?? "",
+      class: frame.class,
+      vendor:
+        typeof frame.vendor_frame === "boolean"
+          ? frame.vendor_frame
+          : VENDOR_PATTERNS.some((pattern) => (frame.file_name ?? frame.file ?? "").includes(pattern)),
+      snippet: Array.isArray(frame.snippet) ? frame.snippet : [],
+    }));
+    const culprit = frames.findIndex((frame) => !frame.vendor);
+
+    return [
+      section(
+        content.class || "Exception",
+        code(content.message || ""),
+        content.file ? el("div", { class: "muted" }, `${content.file}:${content.line ?? 0}`) : null
+      ),
+      frames.length > 0 &&
+        section(
+          "Stack trace",
+          frames.map((frame, index) =>
+            el(
+              "div",
+              { class: "frame" + (frame.vendor ? " vendor" : "") + (index === culprit ? " culprit" : "") },
+              el("div", { class: "mono" }, `${frame.class ? frame.class + "::" : ""}${frame.method}`),
+              el("div", { class: "muted" }, `${frame.file}:${frame.line}`),
+              index === culprit && frame.snippet.length > 0
+                ? el(
+                    "pre",
+                    { class: "mono snippet" },
+                    frame.snippet.map((line) =>
+                      el(
+                        "div",
+                        { class: line.line_number === frame.line ? "current" : "" },
+                        `${String(line.line_number).padStart(5)}  ${line.text ?? ""}`
+                      )
+                    )
+                  )
+                : null
+            )
+          )
+        ),
+      content.context && section("Context", code(content.context)),
+    ];
+  },
+};
+
+// ---- data ----
+
+async function loadTypes() {
+  const response = await fetch(api("/api/types"));
+  const body = await response.json();
+  EVENT_TYPES = body.types;
+  VENDOR_PATTERNS = body.vendor_patterns ?? VENDOR_PATTERNS;
+  state.enabledTypes = new Set(EVENT_TYPES.map((t) => t.name));
+}
+
+async function loadEvents() {
+  const response = await fetch(api("/api/events?limit=1000"));
+  const page = await response.json();
+  // Keep anything the stream delivered while the request was in flight
+  const newest = page.events.length ? page.events[0].id : 0;
+  state.events = state.events.filter((e) => e.id > newest).concat(page.events);
+  renderList();
+}
+
+// Coalesce bursts of streamed events into one re-render per frame
+let renderPending = false;
+function scheduleRender() {
+  if (renderPending) return;
+  renderPending = true;
+  requestAnimationFrame(() => {
+    renderPending = false;
+    renderList();
+  });
+}
+
+function connect() {
+  const source = new EventSource(api("/api/stream"));
+  source.onopen = () => ($("status").textContent = "live");
+  source.onerror = () => ($("status").textContent = "reconnecting…");
+  source.addEventListener("event", (message) => {
+    const event = JSON.parse(message.data);
+    state.events.unshift(event);
+    if (state.events.length > MAX_EVENTS) state.events.length = MAX_EVENTS;
+    scheduleRender();
+  });
+  // We fell behind the feed and missed events - reload the list
+  source.addEventListener("lagged", () => loadEvents());
+}
+
+$("search").addEventListener("input", (e) => {
+  state.search = e.target.value.trim();
+  renderList();
+});
+
+$("clear").addEventListener("click", async () => {
+  await fetch(api("/api/events"), { method: "DELETE" });
+  state.events = [];
+  renderList();
+});
+
+loadTypes().then(() => {
+  renderTypeFilters();
+  connect();
+  loadEvents();
+});
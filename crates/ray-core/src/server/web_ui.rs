@@ -0,0 +1,36 @@
+// server/web_ui.rs - browser UI embedded in the binary and served under /ui
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::header;
+use hyper::{Response, StatusCode};
+
+const INDEX_HTML: &str = include_str!("ui/index.html");
+const APP_JS: &str = include_str!("ui/app.js");
+const STYLE_CSS: &str = include_str!("ui/style.css");
+
+/// Serve an embedded asset, `None` if the path is not part of the UI
+pub fn handle_ui_request(path: &str) -> Option<Response<Full<Bytes>>> {
+    let (body, content_type) = asset(path)?;
+
+    Some(
+        Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, content_type)
+            .body(Full::new(Bytes::from_static(body.as_bytes())))
+            .unwrap(),
+    )
+}
+
+/// Whether the path is one of the embedded files; they hold no event data
+pub fn is_asset(path: &str) -> bool {
+    asset(path).is_some()
+}
+
+fn asset(path: &str) -> Option<(&'static str, &'static str)> {
+    match path {
+        "/ui" | "/ui/" | "/ui/index.html" => Some((INDEX_HTML, "text/html; charset=utf-8")),
+        "/ui/app.js" => Some((APP_JS, "text/javascript; charset=utf-8")),
+        "/ui/style.css" => Some((STYLE_CSS, "text/css; charset=utf-8")),
+        _ => None,
+    }
+}
@@ -3,8 +3,8 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 // Import from the crate we're testing
-use rust_ray_cli::events::process_event;
-use rust_ray_cli::event_storage::EventStorage;
+use ray_core::events::process_event;
+use ray_core::event_storage::EventStorage;
 
 fn create_sample_event(index: usize) -> Value {
     json!({
@@ -1,8 +1,12 @@
+use crate::config::config;
 use crate::event_details::{render_event_details_panel, EventDetailsProps};
-use crate::event_list::render_event_list_panel;
+use crate::event_filter::EventFilter;
+use crate::event_list::{
+    render_event_list_panel, render_exception_groups_panel, render_internal_log_panel,
+};
 use crate::event_storage::EventStorage;
 use crate::events::EventType;
-use crate::ui_components::background_color;
+use crate::ui_components::{background_color, render_toasts};
 use gpui::prelude::*;
 use gpui::{
     actions, div, px, size, App, Application, Bounds, ClipboardItem, IntoElement, Render,
@@ -24,6 +28,13 @@ pub struct MyApp {
     total_rows: usize,
     scroll_handle: UniformListScrollHandle,
     event_type_filters: HashSet<EventType>,
+    /// Projects whose events are hidden; see `RAY_LISTENERS`
+    hidden_projects: HashSet<String>,
+    show_exception_groups: bool,
+    /// Show the app's own diagnostics instead of the events
+    show_internal_log: bool,
+    selected_exception_group: Option<String>,
+    show_occurrence_diff: bool,
     // Performance optimization: cached filtered events
     filter_cache: RefCell<HashMap<u64, Arc<Vec<crate::events::EventEntry>>>>,
     cache_generation: RefCell<u64>,
@@ -40,6 +51,11 @@ impl MyApp {
             total_rows: 0,
             scroll_handle: UniformListScrollHandle::new(),
             event_type_filters,
+            hidden_projects: HashSet::new(),
+            show_exception_groups: false,
+            show_internal_log: false,
+            selected_exception_group: None,
+            show_occurrence_diff: false,
             filter_cache: RefCell::new(HashMap::new()),
             cache_generation: RefCell::new(0),
         }
@@ -48,6 +64,7 @@ impl MyApp {
     pub fn clear_events(&mut self, _cx: &mut Context<Self>) {
         self.payload_storage.clear_events();
         self.selected_row = Some(0);
+        self.selected_exception_group = None;
         self.invalidate_cache();
         _cx.notify();
     }
@@ -63,6 +80,20 @@ impl MyApp {
         cx.write_to_clipboard(ClipboardItem::new_string(text));
     }
 
+    pub fn open_in_editor(&mut self, file: &str, line: u64, _cx: &mut Context<Self>) {
+        if let Err(e) = config().editor.open(file, line) {
+            self.payload_storage.error(
+                "App",
+                &format!("Failed to open {file}:{line} in editor: {e:#}"),
+            );
+        }
+    }
+
+    pub fn dismiss_toast(&mut self, id: u64, cx: &mut Context<Self>) {
+        self.payload_storage.notifications().toasts().dismiss(id);
+        cx.notify();
+    }
+
     pub fn toggle_event_type_filter(&mut self, event_type: EventType, cx: &mut Context<Self>) {
         if self.event_type_filters.contains(&event_type) {
             self.event_type_filters.remove(&event_type);
@@ -74,6 +105,53 @@ impl MyApp {
         cx.notify();
     }
 
+    pub fn toggle_project_filter(&mut self, project: String, cx: &mut Context<Self>) {
+        if !self.hidden_projects.remove(&project) {
+            self.hidden_projects.insert(project);
+        }
+        self.selected_row = Some(0);
+        self.invalidate_cache();
+        cx.notify();
+    }
+
+    pub fn toggle_exception_groups(&mut self, cx: &mut Context<Self>) {
+        self.show_exception_groups = !self.show_exception_groups;
+        self.selected_exception_group = None;
+        self.selected_row = Some(0);
+        self.invalidate_cache();
+        cx.notify();
+    }
+
+    pub fn toggle_internal_log(&mut self, cx: &mut Context<Self>) {
+        self.show_internal_log = !self.show_internal_log;
+        self.show_exception_groups = false;
+        self.selected_exception_group = None;
+        self.selected_row = Some(0);
+        self.invalidate_cache();
+        cx.notify();
+    }
+
+    /// Drill down into the occurrences of a single exception group (`None` goes back)
+    pub fn select_exception_group(&mut self, fingerprint: Option<String>, cx: &mut Context<Self>) {
+        self.selected_exception_group = fingerprint;
+        self.selected_row = Some(0);
+        self.invalidate_cache();
+        cx.notify();
+    }
+
+    pub fn toggle_occurrence_diff(&mut self, cx: &mut Context<Self>) {
+        self.show_occurrence_diff = !self.show_occurrence_diff;
+        cx.notify();
+    }
+
+    /// Select the row showing the given event, if it is visible with the current filters
+    pub fn select_event_by_id(&mut self, id: u64, cx: &mut Context<Self>) {
+        if let Some(index) = self.get_filtered_events().iter().position(|e| e.id == id) {
+            self.selected_row = Some(index);
+            cx.notify();
+        }
+    }
+
     pub fn is_row_selected(&self, index: usize) -> bool {
         self.selected_row == Some(index)
     }
@@ -89,17 +167,33 @@ impl MyApp {
         // Cache miss - compute filtered events
         let all_events = self.payload_storage.get_events_optimized();
 
+        // Drilling into an exception group shows only its occurrences
+        let filter = match self
+            .selected_exception_group
+            .as_ref()
+            .and_then(|fingerprint| self.payload_storage.get_exception_group(fingerprint))
+        {
+            Some(group) => EventFilter::new().with_ids(group.occurrence_ids),
+            None => {
+                let filter = EventFilter::new().with_types(self.event_type_filters.iter().copied());
+                if self.hidden_projects.is_empty() {
+                    filter
+                } else {
+                    filter.with_projects(
+                        config()
+                            .server
+                            .projects()
+                            .map(|project| project.name.clone())
+                            .filter(|name| !self.hidden_projects.contains(name)),
+                    )
+                }
+            }
+        };
+
         // Use iterator adaptors for better performance
         let filtered: Vec<crate::events::EventEntry> = all_events
             .iter()
-            .filter(|event| {
-                // Filter by event type - now with type safety!
-                if let Ok(event_type) = event.event_type.parse::<EventType>() {
-                    self.event_type_filters.contains(&event_type)
-                } else {
-                    false // Filter out unknown event types
-                }
-            })
+            .filter(|event| filter.matches(event))
             .map(|arc_event| (**arc_event).clone()) // Dereference Arc then clone
             .collect();
 
@@ -120,6 +214,10 @@ impl MyApp {
         let mut filters: Vec<_> = self.event_type_filters.iter().collect();
         filters.sort();
         filters.hash(&mut hasher);
+        let mut hidden: Vec<_> = self.hidden_projects.iter().collect();
+        hidden.sort();
+        hidden.hash(&mut hasher);
+        self.selected_exception_group.hash(&mut hasher);
 
         // Hash the storage generation to invalidate cache when events change
         self.payload_storage.get_generation().hash(&mut hasher);
@@ -154,21 +252,62 @@ impl Render for MyApp {
             None
         };
 
-        div()
-            .flex()
-            .bg(background_color())
-            .size_full()
-            .child(render_event_list_panel(
+        let exception_group =
+            selected_entry.and_then(|entry| self.payload_storage.get_exception_group_for(entry));
+        let previous_occurrence =
+            selected_entry
+                .zip(exception_group.as_ref())
+                .and_then(|(entry, group)| {
+                    let position = group.occurrence_ids.iter().position(|id| *id == entry.id)?;
+                    let previous_id = group.occurrence_ids.get(position.checked_sub(1)?)?;
+                    self.payload_storage.get_event(*previous_id)
+                });
+
+        let cache_history =
+            selected_entry.and_then(|entry| self.payload_storage.get_cache_history_for(entry));
+
+        let selected_group = self
+            .selected_exception_group
+            .as_ref()
+            .and_then(|fingerprint| self.payload_storage.get_exception_group(fingerprint));
+
+        let list_panel = if self.show_internal_log {
+            render_internal_log_panel(&self.payload_storage.internal_log(), cx)
+        } else if self.show_exception_groups && selected_group.is_none() {
+            render_exception_groups_panel(&self.payload_storage.get_exception_groups(), cx)
+        } else {
+            render_event_list_panel(
                 events.as_ref(), // Pass slice instead of owned vector
                 &self.event_type_filters,
+                &self.hidden_projects,
                 self.selected_row,
                 &self.scroll_handle,
+                selected_group.as_ref(),
                 cx,
-            ))
+            )
+        };
+
+        let toasts = self.payload_storage.notifications().toasts().all();
+
+        div()
+            .relative()
+            .flex()
+            .bg(background_color())
+            .size_full()
+            .child(list_panel)
             .child(render_event_details_panel(
-                EventDetailsProps { selected_entry },
+                EventDetailsProps {
+                    selected_entry,
+                    exception_group: exception_group.as_ref(),
+                    previous_occurrence: previous_occurrence.as_deref(),
+                    show_occurrence_diff: self.show_occurrence_diff,
+                    cache_history: cache_history.as_ref(),
+                },
                 cx,
             ))
+            .when(!toasts.is_empty(), |this| {
+                this.child(render_toasts(&toasts, cx))
+            })
     }
 }
 
@@ -1,12 +1,26 @@
-use crate::events::{get_ui_renderer, EventEntry};
-use crate::ui_components::{copy_button, text_primary_color, text_secondary_color};
+use crate::events::cache_timeline::CacheKeyHistory;
+use crate::events::exception_groups::{
+    diff_lines, occurrence_text, parse_exception, DiffKind, ExceptionGroup,
+};
+use crate::events::EventEntry;
+use crate::renderers::get_ui_renderer;
+use crate::ui_components::{
+    copy_button, hover_color, selection_color, text_primary_color, text_secondary_color,
+};
 use gpui::prelude::*;
-use gpui::{div, Context, Div};
+use gpui::{div, rgb, Context, Div, FontWeight};
 use std::cell::RefCell;
+use std::collections::HashSet;
 
 // LAZY LOADING: Event details with deferred content loading
 pub struct EventDetailsProps<'a> {
     pub selected_entry: Option<&'a EventEntry>,
+    pub exception_group: Option<&'a ExceptionGroup>,
+    /// Occurrence of the same exception group stored just before the selected one
+    pub previous_occurrence: Option<&'a EventEntry>,
+    pub show_occurrence_diff: bool,
+    /// Timeline of the selected cache entry's key
+    pub cache_history: Option<&'a CacheKeyHistory>,
 }
 
 // PERFORMANCE: Cached detail rendering
@@ -32,19 +46,218 @@ pub fn render_event_details_panel(
         .py_6()
         .overflow_hidden()
         .child(match props.selected_entry {
-            Some(entry) => render_event_details(entry, cx),
+            Some(entry) => render_event_details(entry, &props, cx),
             None => render_no_selection_state(),
         })
 }
 
-fn render_event_details(entry: &EventEntry, cx: &mut Context<crate::app::MyApp>) -> Div {
+fn render_event_details(
+    entry: &EventEntry,
+    props: &EventDetailsProps,
+    cx: &mut Context<crate::app::MyApp>,
+) -> Div {
     div()
         .flex()
         .flex_col()
         .gap_6()
         .h_full()
         .child(render_event_header(entry, cx))
+        .when_some(props.exception_group, |d, group| {
+            d.child(render_exception_group_summary(entry, group, props, cx))
+        })
         .child(render_event_content(entry, cx))
+        .when_some(props.cache_history, |d, history| {
+            d.child(render_cache_key_history(entry, history, cx))
+        })
+}
+
+fn render_cache_key_history(
+    entry: &EventEntry,
+    history: &CacheKeyHistory,
+    cx: &mut Context<crate::app::MyApp>,
+) -> Div {
+    let flagged_ids: HashSet<u64> = history
+        .warnings
+        .iter()
+        .flat_map(|warning| warning.event_ids())
+        .collect();
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_2()
+        .pt_4()
+        .border_t_1()
+        .border_color(crate::ui_components::border_color())
+        .max_h_64()
+        .overflow_hidden()
+        .child(
+            div()
+                .text_xs()
+                .font_weight(FontWeight::MEDIUM)
+                .text_color(text_secondary_color())
+                .child(format!("KEY HISTORY ({} events)", history.entries.len())),
+        )
+        .children(history.warnings.iter().map(|warning| {
+            div()
+                .text_xs()
+                .text_color(rgb(0xf59e0b))
+                .child(format!("⚠ {}", warning.description()))
+        }))
+        .children(history.entries.iter().rev().map(|timeline_entry| {
+            let id = timeline_entry.id;
+            let is_current = id == entry.id;
+            let ttl = timeline_entry
+                .expiration_seconds
+                .map(|seconds| format!("{seconds}s"))
+                .or_else(|| timeline_entry.ttl.clone())
+                .unwrap_or_default();
+            let value = timeline_entry
+                .value
+                .as_ref()
+                .map(|value| {
+                    let value = value.to_string();
+                    if value.len() > 60 {
+                        format!("{}...", value.chars().take(57).collect::<String>())
+                    } else {
+                        value
+                    }
+                })
+                .unwrap_or_default();
+
+            div()
+                .flex()
+                .flex_row()
+                .gap_3()
+                .text_xs()
+                .font_family("monospace")
+                .cursor_pointer()
+                .hover(|style| style.bg(hover_color()))
+                .when(is_current, |d| d.bg(selection_color()))
+                .on_mouse_down(
+                    gpui::MouseButton::Left,
+                    cx.listener(move |this, _event, _window, cx| {
+                        this.select_event_by_id(id, cx);
+                    }),
+                )
+                .child(
+                    div()
+                        .text_color(text_secondary_color())
+                        .child(timeline_entry.timestamp.clone()),
+                )
+                .child(
+                    div()
+                        .w_24()
+                        .text_color(cache_operation_color(&timeline_entry.operation))
+                        .child(timeline_entry.operation.clone()),
+                )
+                .child(div().w_12().text_color(text_secondary_color()).child(ttl))
+                .child(div().flex_1().text_color(text_primary_color()).child(value))
+                .when(flagged_ids.contains(&id), |d| {
+                    d.child(div().text_color(rgb(0xf59e0b)).child("⚠"))
+                })
+        }))
+}
+
+fn cache_operation_color(operation: &str) -> gpui::Hsla {
+    match operation {
+        "Hit" => rgb(0x22c55e).into(),
+        "Missed" => rgb(0xf59e0b).into(),
+        "Key written" => rgb(0x3b82f6).into(),
+        "Forgotten" => rgb(0xef4444).into(),
+        _ => text_secondary_color(),
+    }
+}
+
+fn render_exception_group_summary(
+    entry: &EventEntry,
+    group: &ExceptionGroup,
+    props: &EventDetailsProps,
+    cx: &mut Context<crate::app::MyApp>,
+) -> Div {
+    let fingerprint = group.fingerprint.clone();
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_2()
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .gap_4()
+                .text_xs()
+                .text_color(text_secondary_color())
+                .child(format!(
+                    "seen {} times • first {} • last {}",
+                    group.count, group.first_seen, group.last_seen
+                ))
+                .child(
+                    div()
+                        .cursor_pointer()
+                        .hover(|style| style.text_color(text_primary_color()))
+                        .on_mouse_down(
+                            gpui::MouseButton::Left,
+                            cx.listener(move |this, _event, _window, cx| {
+                                this.select_exception_group(Some(fingerprint.clone()), cx);
+                            }),
+                        )
+                        .child("occurrences"),
+                )
+                .when(props.previous_occurrence.is_some(), |d| {
+                    d.child(
+                        div()
+                            .cursor_pointer()
+                            .hover(|style| style.text_color(text_primary_color()))
+                            .on_mouse_down(
+                                gpui::MouseButton::Left,
+                                cx.listener(|this, _event, _window, cx| {
+                                    this.toggle_occurrence_diff(cx);
+                                }),
+                            )
+                            .child(if props.show_occurrence_diff {
+                                "hide diff"
+                            } else {
+                                "diff with previous"
+                            }),
+                    )
+                }),
+        )
+        .when_some(
+            props
+                .previous_occurrence
+                .filter(|_| props.show_occurrence_diff),
+            |d, previous| d.child(render_occurrence_diff(previous, entry)),
+        )
+}
+
+fn render_occurrence_diff(previous: &EventEntry, current: &EventEntry) -> Div {
+    let (Some(previous), Some(current)) = (parse_exception(previous), parse_exception(current))
+    else {
+        return div();
+    };
+    let diff = diff_lines(&occurrence_text(&previous), &occurrence_text(&current));
+
+    div()
+        .max_h_64()
+        .overflow_hidden()
+        .p_3()
+        .rounded_md()
+        .bg(rgb(0x18181b))
+        .border_1()
+        .border_color(crate::ui_components::border_color())
+        .font_family("monospace")
+        .text_xs()
+        .children(diff.into_iter().map(|line| {
+            let (prefix, color) = match line.kind {
+                DiffKind::Same => (" ", text_secondary_color()),
+                DiffKind::Added => ("+", rgb(0x22c55e).into()),
+                DiffKind::Removed => ("-", rgb(0xef4444).into()),
+            };
+            div()
+                .text_color(color)
+                .child(format!("{prefix} {}", line.text))
+        }))
 }
 
 fn render_event_header(entry: &EventEntry, cx: &mut Context<crate::app::MyApp>) -> Div {
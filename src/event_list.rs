@@ -1,18 +1,26 @@
+use crate::app::MyApp;
+use crate::config::config;
+use crate::event_storage::{LogLevel, LogRecord};
+use crate::events::exception_groups::ExceptionGroup;
 use crate::events::{EventEntry, EventType};
 use crate::ui_components::{
     background_color, border_color, hover_color, selection_color, text_primary_color,
     text_secondary_color,
 };
 use gpui::prelude::*;
-use gpui::{div, uniform_list, Context, Div, FontWeight, IntoElement, UniformListScrollHandle};
+use gpui::{
+    div, rgb, uniform_list, Context, Div, FontWeight, IntoElement, UniformListScrollHandle,
+};
 use std::collections::HashSet;
 use std::ops::Range;
 
 pub fn render_event_list_panel(
     events: &[EventEntry], // Use slice instead of Vec reference for better performance
     event_type_filters: &HashSet<EventType>,
+    hidden_projects: &HashSet<String>,
     selected_row: Option<usize>,
     scroll_handle: &UniformListScrollHandle,
+    selected_exception_group: Option<&ExceptionGroup>,
     cx: &mut Context<crate::app::MyApp>,
 ) -> Div {
     div()
@@ -23,39 +31,230 @@ pub fn render_event_list_panel(
         .bg(background_color())
         .border_r_1()
         .border_color(border_color())
-        .child(render_header_with_filters(event_type_filters, cx))
+        .child(match selected_exception_group {
+            Some(group) => render_exception_group_header(group, cx),
+            None => render_header_with_filters(event_type_filters, hidden_projects, cx),
+        })
         .child(render_event_list(events, selected_row, scroll_handle, cx))
 }
 
-fn render_header_with_filters(
-    event_type_filters: &HashSet<EventType>,
+/// List panel showing exceptions aggregated by fingerprint
+pub fn render_exception_groups_panel(
+    groups: &[ExceptionGroup],
     cx: &mut Context<crate::app::MyApp>,
 ) -> Div {
-    // Much simpler - just get all event types from the enum
-    let event_types = EventType::all();
+    div()
+        .flex()
+        .flex_col()
+        .w_80()
+        .h_full()
+        .bg(background_color())
+        .border_r_1()
+        .border_color(border_color())
+        .child(
+            div()
+                .px_4()
+                .py_3()
+                .border_b_1()
+                .border_color(border_color())
+                .child(render_title_row(
+                    "Exception groups",
+                    &[("events", MyApp::toggle_exception_groups)],
+                    cx,
+                )),
+        )
+        .child(if groups.is_empty() {
+            render_empty_state()
+        } else {
+            div().flex_1().flex().flex_col().overflow_hidden().children(
+                groups
+                    .iter()
+                    .map(|group| render_exception_group_row(group, cx))
+                    .collect::<Vec<_>>(),
+            )
+        })
+}
 
+/// List panel showing the app's own diagnostics, like accept errors and bad requests
+pub fn render_internal_log_panel(
+    records: &[LogRecord],
+    cx: &mut Context<crate::app::MyApp>,
+) -> Div {
     div()
         .flex()
         .flex_col()
+        .w_80()
+        .h_full()
+        .bg(background_color())
+        .border_r_1()
+        .border_color(border_color())
+        .child(
+            div()
+                .px_4()
+                .py_3()
+                .border_b_1()
+                .border_color(border_color())
+                .child(render_title_row(
+                    "Internal",
+                    &[("events", MyApp::toggle_internal_log)],
+                    cx,
+                )),
+        )
+        .child(if records.is_empty() {
+            render_empty_state()
+        } else {
+            div().flex_1().flex().flex_col().overflow_hidden().children(
+                records
+                    .iter()
+                    .map(render_internal_log_row)
+                    .collect::<Vec<_>>(),
+            )
+        })
+}
+
+fn render_internal_log_row(record: &LogRecord) -> Div {
+    let level_color: gpui::Hsla = match record.level {
+        LogLevel::Error => rgb(0xef4444).into(),
+        LogLevel::Warn => rgb(0xf59e0b).into(),
+        LogLevel::Info | LogLevel::Debug => text_secondary_color(),
+    };
+
+    div()
         .px_4()
-        .py_3()
+        .py_2()
+        .gap_1()
         .border_b_1()
         .border_color(border_color())
         .child(
-            // Header row with title and clear button
+            div()
+                .flex()
+                .flex_row()
+                .gap_2()
+                .text_xs()
+                .child(div().text_color(level_color).child(record.level.as_str()))
+                .child(
+                    div()
+                        .text_color(text_primary_color())
+                        .child(record.source.clone()),
+                ),
+        )
+        .child(render_event_description_optimized(&record.message))
+        .child(render_event_timestamp_optimized(&record.timestamp))
+}
+
+fn render_exception_group_row(group: &ExceptionGroup, cx: &mut Context<crate::app::MyApp>) -> Div {
+    let fingerprint = group.fingerprint.clone();
+
+    div()
+        .px_4()
+        .py_3()
+        .gap_1()
+        .cursor_pointer()
+        .hover(|style| style.bg(hover_color()))
+        .on_mouse_down(
+            gpui::MouseButton::Left,
+            cx.listener(move |this, _event, _, cx| {
+                this.select_exception_group(Some(fingerprint.clone()), cx);
+            }),
+        )
+        .child(
             div()
                 .flex()
                 .flex_row()
                 .justify_between()
-                .items_center()
-                .mb_3()
+                .child(render_event_label_optimized(&group.class))
                 .child(
                     div()
-                        .text_sm()
-                        .font_weight(FontWeight::MEDIUM)
+                        .text_xs()
                         .text_color(text_primary_color())
-                        .child("Events"),
+                        .child(format!("×{}", group.count)),
+                ),
+        )
+        .child(render_event_description_optimized(&group.message))
+        .child(render_event_timestamp_optimized(&format!(
+            "last seen {}",
+            group.last_seen
+        )))
+}
+
+fn render_exception_group_header(
+    group: &ExceptionGroup,
+    cx: &mut Context<crate::app::MyApp>,
+) -> Div {
+    div()
+        .flex()
+        .flex_col()
+        .gap_1()
+        .px_4()
+        .py_3()
+        .border_b_1()
+        .border_color(border_color())
+        .child(
+            div()
+                .text_xs()
+                .text_color(text_secondary_color())
+                .cursor_pointer()
+                .hover(|style| style.text_color(text_primary_color()))
+                .on_mouse_down(
+                    gpui::MouseButton::Left,
+                    cx.listener(|this, _event, _, cx| {
+                        this.select_exception_group(None, cx);
+                    }),
                 )
+                .child("← all groups"),
+        )
+        .child(render_event_label_optimized(&group.class))
+        .child(
+            div()
+                .text_xs()
+                .text_color(text_secondary_color())
+                .child(format!(
+                    "{} occurrences • first {} • last {}",
+                    group.count, group.first_seen, group.last_seen
+                )),
+        )
+}
+
+/// Switches the list panel to another view
+type ViewToggle = fn(&mut MyApp, &mut Context<MyApp>);
+
+/// Title with view toggle links and the clear button
+fn render_title_row(
+    title: &'static str,
+    toggles: &[(&'static str, ViewToggle)],
+    cx: &mut Context<crate::app::MyApp>,
+) -> Div {
+    div()
+        .flex()
+        .flex_row()
+        .justify_between()
+        .items_center()
+        .child(
+            div()
+                .text_sm()
+                .font_weight(FontWeight::MEDIUM)
+                .text_color(text_primary_color())
+                .child(title),
+        )
+        .child(
+            div()
+                .flex()
+                .flex_row()
+                .gap_3()
+                .children(toggles.iter().map(|&(label, toggle)| {
+                    div()
+                        .text_xs()
+                        .text_color(text_secondary_color())
+                        .cursor_pointer()
+                        .hover(|style| style.text_color(text_primary_color()))
+                        .on_mouse_down(
+                            gpui::MouseButton::Left,
+                            cx.listener(move |this, _event, _, cx| {
+                                toggle(this, cx);
+                            }),
+                        )
+                        .child(label)
+                }))
                 .child(
                     div()
                         .text_xs()
@@ -71,6 +270,35 @@ fn render_header_with_filters(
                         .child("clear"),
                 ),
         )
+}
+
+fn render_header_with_filters(
+    event_type_filters: &HashSet<EventType>,
+    hidden_projects: &HashSet<String>,
+    cx: &mut Context<crate::app::MyApp>,
+) -> Div {
+    // Much simpler - just get all event types from the enum
+    let event_types = EventType::all();
+
+    div()
+        .flex()
+        .flex_col()
+        .px_4()
+        .py_3()
+        .border_b_1()
+        .border_color(border_color())
+        .child(
+            // Header row with title, view toggles and clear button
+            render_title_row(
+                "Events",
+                &[
+                    ("groups", MyApp::toggle_exception_groups),
+                    ("internal", MyApp::toggle_internal_log),
+                ],
+                cx,
+            )
+            .mb_3(),
+        )
         .child(
             // Filters section
             div()
@@ -87,10 +315,70 @@ fn render_header_with_filters(
                     event_types,
                     event_type_filters,
                     cx,
-                )),
+                ))
+                // Only when listeners tag their events with projects
+                .when(config().server.projects().next().is_some(), |filters| {
+                    filters
+                        .child(
+                            div()
+                                .mt_1()
+                                .text_xs()
+                                .text_color(text_secondary_color())
+                                .child("Filter by project:"),
+                        )
+                        .child(render_project_checkboxes(hidden_projects, cx))
+                }),
         )
 }
 
+fn render_project_checkboxes(
+    hidden_projects: &HashSet<String>,
+    cx: &mut Context<crate::app::MyApp>,
+) -> Div {
+    div().flex().flex_col().gap_1().children(
+        config()
+            .server
+            .projects()
+            .map(|project| {
+                let is_enabled = !hidden_projects.contains(&project.name);
+                let checkbox_style = if is_enabled {
+                    text_primary_color()
+                } else {
+                    text_secondary_color()
+                };
+                let name = project.name.clone();
+
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap_2()
+                    .cursor_pointer()
+                    .hover(|style| style.text_color(text_primary_color()))
+                    .on_mouse_down(
+                        gpui::MouseButton::Left,
+                        cx.listener(move |this, _event, _, cx| {
+                            this.toggle_project_filter(name.clone(), cx);
+                        }),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(checkbox_style)
+                            .child(if is_enabled { "☑" } else { "☐" }),
+                    )
+                    .child(div().size_2().rounded_full().bg(rgb(project.color)))
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(checkbox_style)
+                            .child(project.name.clone()),
+                    )
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
 fn render_filter_checkboxes(
     event_types: Vec<EventType>,
     event_type_filters: &HashSet<EventType>,
@@ -188,6 +476,10 @@ fn render_event_uniform_list(
                         } else {
                             background_color()
                         };
+                        let project = entry
+                            .project
+                            .as_deref()
+                            .and_then(|name| config().server.project(name));
 
                         div()
                             .id(("event", index))
@@ -200,6 +492,10 @@ fn render_event_uniform_list(
                                 div.hover(|style| style.bg(hover_color()))
                             })
                             .cursor_pointer()
+                            // A stripe in the project's color tells apps apart at a glance
+                            .when_some(project, |row, project| {
+                                row.border_l_4().border_color(rgb(project.color))
+                            })
                             .on_click(cx.listener(move |this, _event, _window, cx| {
                                 this.select_row(index, cx);
                             }))
@@ -209,7 +505,23 @@ fn render_event_uniform_list(
                                     .flex_row()
                                     .justify_between()
                                     .child(render_event_label_optimized(&entry.label))
-                                    .child(render_event_timestamp_optimized(&entry.timestamp)),
+                                    .child(
+                                        div()
+                                            .flex()
+                                            .flex_row()
+                                            .gap_2()
+                                            .when_some(project, |meta, project| {
+                                                meta.child(
+                                                    div()
+                                                        .text_xs()
+                                                        .text_color(rgb(project.color))
+                                                        .child(project.name.clone()),
+                                                )
+                                            })
+                                            .child(render_event_timestamp_optimized(
+                                                &entry.timestamp,
+                                            )),
+                                    ),
                             )
                             .child(render_event_description_optimized(&entry.description))
                     })
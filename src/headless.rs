@@ -0,0 +1,23 @@
+// headless.rs - server-only front-end for builds without a window or terminal UI
+use crate::event_storage::EventStorage;
+use crate::notifications::sinks::TerminalSink;
+use std::sync::Arc;
+
+/// Keep the server running until Ctrl-C; stored events show up as console log lines
+pub async fn run_headless(
+    payload_storage: Arc<EventStorage>,
+    shutdown_tx: tokio::sync::oneshot::Sender<()>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Toasts have nowhere to appear, so alerts go to the terminal instead
+    payload_storage
+        .notifications()
+        .add_sink(Box::new(TerminalSink));
+    payload_storage.info("Headless", "Running without a UI, press Ctrl-C to stop");
+
+    let result = tokio::signal::ctrl_c().await;
+
+    payload_storage.info("Headless", "Stopping server");
+    let _ = shutdown_tx.send(());
+
+    result.map_err(Into::into)
+}
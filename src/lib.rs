@@ -1,13 +1,24 @@
 // lib.rs - Expose modules for integration tests and external use
-pub mod events;
-pub mod event_storage;
+#[cfg(feature = "gui")]
 pub mod ui_components;
-pub mod performance;
-pub mod server;
+#[cfg(feature = "gui")]
+pub mod renderers;
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(feature = "gui")]
 pub mod app;
+#[cfg(feature = "gui")]
 pub mod event_details;
+#[cfg(feature = "gui")]
 pub mod event_list;
+pub mod headless;
+
+// Processing, storage and the server live in the ray-core crate
+pub use ray_core::{
+    config, editor, event_filter, event_storage, events, notifications, performance, redaction,
+    scripts, server,
+};
 
 // Re-export commonly used items
 pub use events::{process_event, EventEntry};
-pub use event_storage::EventStorage;
\ No newline at end of file
+pub use event_storage::EventStorage;
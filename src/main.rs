@@ -1,28 +1,86 @@
 // main.rs
+#[cfg(feature = "gui")]
 mod app;
+#[cfg(feature = "gui")]
 mod event_details;
+#[cfg(feature = "gui")]
 mod event_list;
-mod event_storage;
-mod events;
-mod performance;
-mod server;
+mod headless;
+#[cfg(feature = "gui")]
+mod renderers;
+#[cfg(feature = "tui")]
+mod tui;
+#[cfg(feature = "gui")]
 mod ui_components;
 
+#[cfg(any(feature = "gui", feature = "tui"))]
+use ray_core::{config, event_filter, events, redaction};
+use ray_core::{event_storage, notifications, server};
+
+#[cfg(feature = "gui")]
 use app::run_app;
 use event_storage::EventStorage;
+use headless::run_headless;
 use server::start_server;
 use std::sync::Arc;
 use tokio::sync::oneshot;
+#[cfg(feature = "tui")]
+use tui::run_tui;
+
+/// Which front-end runs alongside the server, limited to the compiled-in features
+enum Frontend {
+    #[cfg(feature = "gui")]
+    Gui,
+    #[cfg(feature = "tui")]
+    Tui,
+    Headless,
+}
+
+impl Frontend {
+    #[cfg(feature = "gui")]
+    const DEFAULT: Self = Self::Gui;
+    #[cfg(not(feature = "gui"))]
+    const DEFAULT: Self = Self::Headless;
+
+    /// `--headless` and `--tui` override the default, which is the GUI when it is built
+    fn from_args() -> Self {
+        let flag = |name: &str| std::env::args().any(|arg| arg == name);
+
+        if flag("--headless") {
+            return Self::Headless;
+        }
+        #[cfg(feature = "tui")]
+        if flag("--tui") {
+            return Self::Tui;
+        }
+        Self::DEFAULT
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "gui")]
+            Self::Gui => "GUI",
+            #[cfg(feature = "tui")]
+            Self::Tui => "TUI",
+            Self::Headless => "headless",
+        }
+    }
+}
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Our own messages at info and everything else at warn, unless RUST_LOG says otherwise
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn,ray=info"))
+        .init();
+
     let event_storage = Arc::new(EventStorage::new());
+    let frontend = Frontend::from_args();
 
-    event_storage.info("Main", "Starting in GUI mode");
+    event_storage.info("Main", &format!("Starting in {} mode", frontend.name()));
 
     // Log system information
-    event_storage.info("Main", &format!("OS: {}", std::env::consts::OS));
-    event_storage.info(
+    event_storage.debug("Main", &format!("OS: {}", std::env::consts::OS));
+    event_storage.debug(
         "Main",
         &format!(
             "Current dir: {:?}",
@@ -43,87 +101,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     });
 
-    // Run the gpui application
-    event_storage.info("Main", "Initializing GUI application");
-    event_storage.info("Main", "Starting GUI event loop");
+    let result = match frontend {
+        #[cfg(feature = "gui")]
+        Frontend::Gui => {
+            // Run the gpui application
+            event_storage.info("Main", "Initializing GUI application");
+            event_storage.info("Main", "Starting GUI event loop");
 
-    let result = run_app(event_storage, shutdown_tx);
+            run_app(event_storage, shutdown_tx)
+        }
+        #[cfg(feature = "tui")]
+        Frontend::Tui => run_tui(event_storage, shutdown_tx),
+        Frontend::Headless => run_headless(event_storage, shutdown_tx).await,
+    };
     
     // Wait for server to shutdown gracefully
     let _ = server_handle.await;
     
     result
 }
-
-
-#[cfg(test)]
-mod tests {
-    use crate::events::process_event;
-    use serde_json::json;
-
-    #[test]
-    fn test_table_http_detection() {
-        let table_event = json!({
-            "type": "table",
-            "content": {
-                "label": "Http",
-                "values": {
-                    "Method": "GET", 
-                    "URL": "https://api.example.com/test"
-                }
-            }
-        });
-
-        let result = process_event("table", &table_event).unwrap();
-        assert_eq!(result.event_type, "request");
-        assert!(result.label.contains("HTTP"));
-    }
-
-    #[test]
-    fn test_table_cache_detection() {
-        let table_event = json!({
-            "type": "table", 
-            "content": {
-                "label": "Cache",
-                "values": {
-                    "Event": "Hit",
-                    "Key": "test:key"
-                }
-            }
-        });
-
-        let result = process_event("table", &table_event).unwrap();
-        assert_eq!(result.event_type, "cache");
-        assert!(result.label.contains("Cache"));
-    }
-
-    #[test]
-    fn test_process_application_log() {
-        let test_event = json!({
-            "type": "application_log",
-            "content": {
-                "value": "Test application log message"
-            }
-        });
-
-        let result = process_event("application_log", &test_event).unwrap();
-        assert_eq!(result.label, "Application Log");
-        assert_eq!(result.description, "Test application log message");
-        assert_eq!(result.content_type, "custom_ui");
-    }
-
-    #[test]
-    fn test_process_log_event() {
-        let test_event = json!({
-            "type": "log",
-            "content": {
-                "values": ["Test log message", "Another value"]
-            }
-        });
-
-        let result = process_event("log", &test_event).unwrap();
-        assert_eq!(result.label, "Log");
-        assert_eq!(result.description, "Test log message");
-        assert_eq!(result.content_type, "custom_ui");
-    }
-}
\ No newline at end of file
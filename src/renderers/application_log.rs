@@ -1,53 +1,14 @@
-use crate::events::base::{extract_timestamp, EventEntry};
-use crate::events::processors::process_application_log_event;
-use crate::events::types::ProcessedEvent;
+use crate::events::base::EventEntry;
 use crate::ui_components::{
-    border_color, text_monospace_color, text_primary_color, text_secondary_color,
+    border_color, editor_link, text_monospace_color, text_primary_color, text_secondary_color,
 };
-use anyhow::Result;
 use gpui::prelude::*;
 use gpui::{div, Context, Div};
 use serde_json::Value;
 
-pub fn process(payload: &Value) -> Result<EventEntry> {
-    let mut entry = EventEntry {
-        timestamp: extract_timestamp(payload),
-        label: "Application Log".to_string(),
-        description: String::new(),
-        content_type: "custom_ui".to_string(),
-        event_type: "application_log".to_string(),
-        raw_payload: payload.clone(),
-    };
-
-    if let Some(content) = payload.get("content") {
-        // Process using the new architecture
-        let processed_event = process_application_log_event(content)?;
-
-        // Set labels and descriptions based on processed event
-        if let ProcessedEvent::ApplicationLog(ref app_log_event) = processed_event {
-            entry.label = "Application Log".to_string();
-            entry.description = if !app_log_event.message.is_empty() {
-                if app_log_event.message.len() > 50 {
-                    format!("{}...", &app_log_event.message[..50].trim())
-                } else {
-                    app_log_event.message.clone()
-                }
-            } else {
-                "Empty log".to_string()
-            };
-        } else {
-            return Err(anyhow::anyhow!(
-                "Unexpected event type from application log processor"
-            ));
-        }
-    }
-
-    Ok(entry)
-}
-
 pub fn render_application_log_event(
     entry: &EventEntry,
-    _cx: &mut Context<crate::app::MyApp>,
+    cx: &mut Context<crate::app::MyApp>,
 ) -> Div {
     let content = entry
         .raw_payload
@@ -61,7 +22,7 @@ pub fn render_application_log_event(
         .gap_6()
         .child(render_app_log_content(&content))
         .child(render_app_log_context(&content))
-        .child(render_origin_info(entry))
+        .child(render_origin_info(entry, cx))
 }
 
 fn render_app_log_content(content: &Value) -> Div {
@@ -136,7 +97,7 @@ fn render_app_log_context(content: &Value) -> Div {
     div() // Empty div if no context
 }
 
-fn render_origin_info(entry: &EventEntry) -> Div {
+fn render_origin_info(entry: &EventEntry, cx: &mut Context<crate::app::MyApp>) -> Div {
     if let Some(origin) = entry.raw_payload.get("origin") {
         let file = origin.get("file").and_then(|f| f.as_str()).unwrap_or("");
         let line = origin
@@ -158,7 +119,12 @@ fn render_origin_info(entry: &EventEntry) -> Div {
                         .text_xs()
                         .text_color(text_secondary_color())
                         .opacity(0.7)
-                        .child(format!("{file}:{line} • {hostname}")),
+                        .child(editor_link(
+                            format!("{file}:{line} • {hostname}"),
+                            file,
+                            line,
+                            cx,
+                        )),
                 )
         } else {
             div() // Empty div if no origin info
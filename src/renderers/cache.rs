@@ -1,47 +1,12 @@
-use crate::events::base::{extract_timestamp, EventEntry};
+use crate::events::base::EventEntry;
 use crate::events::processors::cache::process_cache_event;
 use crate::events::types::{CacheEvent, ProcessedEvent};
-use crate::ui_components::{border_color, text_primary_color, text_secondary_color};
-use anyhow::Result;
+use crate::ui_components::{border_color, editor_link, text_primary_color, text_secondary_color};
 use gpui::prelude::*;
 use gpui::{div, rgb, Context, Div, FontWeight};
 use serde_json::Value;
 
-pub fn process(payload: &Value) -> Result<EventEntry> {
-    let mut entry = EventEntry {
-        timestamp: extract_timestamp(payload),
-        label: "cache".to_string(),
-        description: String::new(),
-        content_type: "custom_ui".to_string(),
-        event_type: "cache".to_string(),
-        raw_payload: payload.clone(),
-    };
-
-    if let Some(content) = payload.get("content") {
-        // Process using the new architecture
-        let processed_event = process_cache_event(content)?;
-
-        // Set labels and descriptions based on processed event
-        if let ProcessedEvent::Cache(ref cache_event) = processed_event {
-            entry.label = format!("Cache: {}", cache_event.operation);
-            entry.description = match cache_event.operation.as_str() {
-                "Hit" => format!("Cache hit for: {}", cache_event.key),
-                "Missed" => format!("Cache miss for: {}", cache_event.key),
-                "Key written" => format!("Cache write: {}", cache_event.key),
-                "Forgotten" => format!("Cache key forgotten: {}", cache_event.key),
-                _ => format!("{} ({})", cache_event.operation, cache_event.key),
-            };
-        } else {
-            return Err(anyhow::anyhow!(
-                "Unexpected event type from cache processor"
-            ));
-        }
-    }
-
-    Ok(entry)
-}
-
-pub fn render_cache_event(entry: &EventEntry, _cx: &mut Context<crate::app::MyApp>) -> Div {
+pub fn render_cache_event(entry: &EventEntry, cx: &mut Context<crate::app::MyApp>) -> Div {
     if let Some(content) = entry.raw_payload.get("content") {
         if let Ok(ProcessedEvent::Cache(cache_event)) = process_cache_event(content) {
             div()
@@ -53,7 +18,7 @@ pub fn render_cache_event(entry: &EventEntry, _cx: &mut Context<crate::app::MyAp
                 .when(has_cache_metadata(&cache_event), |d| {
                     d.child(render_cache_metadata(&cache_event))
                 })
-                .child(render_cache_origin_info(entry))
+                .child(render_cache_origin_info(entry, cx))
         } else {
             div().child("Invalid cache event data")
         }
@@ -269,7 +234,7 @@ fn format_duration(seconds: u64) -> String {
     }
 }
 
-fn render_cache_origin_info(entry: &EventEntry) -> Div {
+fn render_cache_origin_info(entry: &EventEntry, cx: &mut Context<crate::app::MyApp>) -> Div {
     if let Some(origin) = entry.raw_payload.get("origin") {
         let file = origin.get("file").and_then(Value::as_str).unwrap_or("");
         let line = origin
@@ -284,7 +249,7 @@ fn render_cache_origin_info(entry: &EventEntry) -> Div {
             .text_xs()
             .text_color(text_secondary_color())
             .opacity(0.7)
-            .child(format!("{file}:{line}"))
+            .child(editor_link(format!("{file}:{line}"), file, line, cx))
     } else {
         div()
     }
@@ -0,0 +1,81 @@
+use crate::events::base::EventEntry;
+use crate::events::registry;
+use crate::ui_components::{
+    border_color, editor_link, text_monospace_color, text_primary_color, text_secondary_color,
+};
+use gpui::prelude::*;
+use gpui::{div, Context, Div};
+
+/// Generic view for declarative custom types: their mapped fields as a label/value list
+pub fn render_custom_event(entry: &EventEntry, cx: &mut Context<crate::app::MyApp>) -> Div {
+    let fields = registry()
+        .definition(&entry.event_type)
+        .map(|definition| definition.field_values(&entry.raw_payload))
+        .unwrap_or_default();
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_6()
+        .when(!entry.description.is_empty(), |this| {
+            this.child(
+                div()
+                    .text_sm()
+                    .text_color(text_primary_color())
+                    .child(entry.description.clone()),
+            )
+        })
+        .child(
+            div()
+                .flex()
+                .flex_col()
+                .gap_2()
+                .children(fields.into_iter().map(|(label, value)| {
+                    div()
+                        .flex()
+                        .flex_row()
+                        .gap_4()
+                        .text_xs()
+                        .child(
+                            div()
+                                .w_32()
+                                .flex_shrink_0()
+                                .text_color(text_secondary_color())
+                                .child(label),
+                        )
+                        .child(
+                            div()
+                                .font_family("monospace")
+                                .text_color(text_monospace_color())
+                                .child(value),
+                        )
+                })),
+        )
+        .child(render_origin_info(entry, cx))
+}
+
+fn render_origin_info(entry: &EventEntry, cx: &mut Context<crate::app::MyApp>) -> Div {
+    let Some(origin) = entry.raw_payload.get("origin") else {
+        return div();
+    };
+    let file = origin.get("file").and_then(|f| f.as_str()).unwrap_or("");
+    let line = origin
+        .get("line_number")
+        .and_then(|l| l.as_u64())
+        .unwrap_or(0);
+    if file.is_empty() {
+        return div();
+    }
+
+    div()
+        .pt_4()
+        .border_t_1()
+        .border_color(border_color())
+        .child(
+            div()
+                .text_xs()
+                .text_color(text_secondary_color())
+                .opacity(0.7)
+                .child(editor_link(format!("{file}:{line}"), file, line, cx)),
+        )
+}
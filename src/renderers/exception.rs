@@ -0,0 +1,325 @@
+use crate::config::config;
+use crate::events::base::EventEntry;
+use crate::events::frames::{first_application_frame, segment_frames, FrameKind, FrameSegment};
+use crate::events::processors::process_exception_event;
+use crate::events::types::{ProcessedEvent, StackFrame};
+use crate::ui_components::{
+    border_color, editor_link, text_monospace_color, text_primary_color, text_secondary_color,
+};
+use gpui::prelude::*;
+use gpui::{div, rgb, Context, Div};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::ops::Range;
+
+pub fn render_exception_event(entry: &EventEntry, cx: &mut Context<crate::app::MyApp>) -> Div {
+    let content = entry
+        .raw_payload
+        .get("content")
+        .cloned()
+        .unwrap_or_default();
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_6()
+        .child(render_exception_details(&content))
+        .child(render_stack_trace(entry, &content, cx))
+        .child(render_origin_info(entry, cx))
+}
+
+// Header removed for minimal design
+
+fn render_exception_details(content: &Value) -> Div {
+    let class = content
+        .get("class")
+        .and_then(|c| c.as_str())
+        .unwrap_or("Exception");
+    let message = content
+        .get("message")
+        .and_then(|m| m.as_str())
+        .unwrap_or("");
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_2()
+        .child(
+            div()
+                .text_sm()
+                .text_color(text_primary_color())
+                .child(if !message.is_empty() {
+                    format!("{class}: {message}")
+                } else {
+                    class.to_string()
+                }),
+        )
+}
+
+thread_local! {
+    // Vendor frame groups the user expanded, keyed by (entry id, first frame index)
+    static EXPANDED_VENDOR_GROUPS: RefCell<HashSet<(u64, usize)>> = RefCell::new(HashSet::new());
+}
+
+fn is_vendor_group_expanded(entry_id: u64, start: usize) -> bool {
+    EXPANDED_VENDOR_GROUPS.with(|groups| groups.borrow().contains(&(entry_id, start)))
+}
+
+fn toggle_vendor_group(entry_id: u64, start: usize) {
+    EXPANDED_VENDOR_GROUPS.with(|groups| {
+        let mut groups = groups.borrow_mut();
+        if !groups.remove(&(entry_id, start)) {
+            groups.insert((entry_id, start));
+        }
+    });
+}
+
+fn render_stack_trace(
+    entry: &EventEntry,
+    content: &Value,
+    cx: &mut Context<crate::app::MyApp>,
+) -> Div {
+    let Ok(ProcessedEvent::Exception(exception)) = process_exception_event(content) else {
+        return div();
+    };
+    if exception.stack_trace.is_empty() {
+        return div(); // Empty div if no frames
+    }
+
+    let frame_config = &config().frames;
+    let kinds: Vec<FrameKind> = exception
+        .stack_trace
+        .iter()
+        .map(|frame| frame_config.classify(frame))
+        .collect();
+    let application_frames = kinds
+        .iter()
+        .filter(|kind| **kind == FrameKind::Application)
+        .count();
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_3()
+        .child(
+            div()
+                .text_xs()
+                .text_color(text_secondary_color())
+                .opacity(0.7)
+                .child(format!(
+                    "{} frames • {} application",
+                    exception.stack_trace.len(),
+                    application_frames
+                )),
+        )
+        .child(render_frames(entry.id, &exception.stack_trace, &kinds, cx))
+}
+
+fn render_frames(
+    entry_id: u64,
+    frames: &[StackFrame],
+    kinds: &[FrameKind],
+    cx: &mut Context<crate::app::MyApp>,
+) -> Div {
+    let first_application = first_application_frame(kinds);
+    let mut container = div().flex().flex_col().gap_2();
+
+    for segment in segment_frames(kinds) {
+        container = match segment {
+            FrameSegment::Application(index) => container.child(render_single_frame(
+                index,
+                &frames[index],
+                FrameKind::Application,
+                first_application == Some(index),
+                cx,
+            )),
+            // A lone vendor frame is not worth folding
+            FrameSegment::Vendor(range) if range.len() == 1 => {
+                container.child(render_single_frame(
+                    range.start,
+                    &frames[range.start],
+                    FrameKind::Vendor,
+                    false,
+                    cx,
+                ))
+            }
+            FrameSegment::Vendor(range) => {
+                let expanded = is_vendor_group_expanded(entry_id, range.start);
+                let mut container =
+                    container.child(render_vendor_group_toggle(entry_id, &range, expanded, cx));
+                if expanded {
+                    for index in range {
+                        container = container.child(render_single_frame(
+                            index,
+                            &frames[index],
+                            FrameKind::Vendor,
+                            false,
+                            cx,
+                        ));
+                    }
+                }
+                container
+            }
+        };
+    }
+
+    container
+}
+
+fn render_vendor_group_toggle(
+    entry_id: u64,
+    range: &Range<usize>,
+    expanded: bool,
+    cx: &mut Context<crate::app::MyApp>,
+) -> Div {
+    let start = range.start;
+
+    div()
+        .py_1()
+        .text_xs()
+        .text_color(text_secondary_color())
+        .opacity(0.7)
+        .cursor_pointer()
+        .hover(|style| style.text_color(text_primary_color()))
+        .on_mouse_down(
+            gpui::MouseButton::Left,
+            cx.listener(move |_this, _event, _window, cx| {
+                toggle_vendor_group(entry_id, start);
+                cx.notify();
+            }),
+        )
+        .child(format!(
+            "{} {} vendor frames",
+            if expanded { "▾" } else { "▸" },
+            range.len()
+        ))
+}
+
+fn render_single_frame(
+    index: usize,
+    frame: &StackFrame,
+    kind: FrameKind,
+    is_first_application: bool,
+    cx: &mut Context<crate::app::MyApp>,
+) -> Div {
+    let class = frame.class.as_deref().unwrap_or("");
+    let method = &frame.function;
+    let file = frame.file.as_str();
+    let line = frame.line;
+
+    div()
+        .flex()
+        .flex_row()
+        .gap_3()
+        .items_start()
+        .py_2()
+        .when(kind == FrameKind::Vendor, |d| d.opacity(0.6))
+        .when(is_first_application, |d| {
+            d.pl_2().border_l_2().border_color(rgb(0xef4444))
+        })
+        .child(
+            div()
+                .text_xs()
+                .text_color(text_secondary_color())
+                .opacity(0.5)
+                .w_4()
+                .child(format!("{}", index + 1)),
+        )
+        .child(
+            div()
+                .flex_1()
+                .flex()
+                .flex_col()
+                .gap_1()
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(text_primary_color())
+                        .child(format!("{class}::{method}")),
+                )
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(text_secondary_color())
+                        .opacity(0.7)
+                        .child(editor_link(format!("{file}:{line}"), file, line, cx)),
+                )
+                .when(!frame.snippet.is_empty(), |d| {
+                    d.child(render_snippet(frame))
+                }),
+        )
+}
+
+fn render_snippet(frame: &StackFrame) -> Div {
+    div()
+        .mt_1()
+        .p_2()
+        .rounded_md()
+        .bg(rgb(0x18181b))
+        .border_1()
+        .border_color(border_color())
+        .font_family("monospace")
+        .text_xs()
+        .children(frame.snippet.iter().map(|snippet_line| {
+            let is_frame_line = snippet_line.line_number == frame.line;
+            div()
+                .flex()
+                .flex_row()
+                .gap_3()
+                .when(is_frame_line, |d| d.bg(border_color()))
+                .child(
+                    div()
+                        .w_8()
+                        .text_color(text_secondary_color())
+                        .opacity(0.5)
+                        .child(snippet_line.line_number.to_string()),
+                )
+                .child(
+                    div()
+                        .text_color(if is_frame_line {
+                            text_primary_color()
+                        } else {
+                            text_monospace_color()
+                        })
+                        .child(snippet_line.text.clone()),
+                )
+        }))
+}
+
+fn render_origin_info(entry: &EventEntry, cx: &mut Context<crate::app::MyApp>) -> Div {
+    if let Some(origin) = entry.raw_payload.get("origin") {
+        let file = origin.get("file").and_then(|f| f.as_str()).unwrap_or("");
+        let line = origin
+            .get("line_number")
+            .and_then(|l| l.as_u64())
+            .unwrap_or(0);
+        let hostname = origin
+            .get("hostname")
+            .and_then(|h| h.as_str())
+            .unwrap_or("");
+
+        if !file.is_empty() {
+            div()
+                .pt_4()
+                .border_t_1()
+                .border_color(border_color())
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(text_secondary_color())
+                        .opacity(0.7)
+                        .child(editor_link(
+                            format!("{file}:{line} • {hostname}"),
+                            file,
+                            line,
+                            cx,
+                        )),
+                )
+        } else {
+            div() // Empty div if no origin info
+        }
+    } else {
+        div() // Empty div if no origin
+    }
+}
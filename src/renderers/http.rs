@@ -1,51 +1,15 @@
 use std::collections::BTreeMap;
 
-use crate::events::base::{extract_timestamp, EventEntry};
+use crate::events::base::EventEntry;
 use crate::events::processors::http::process_http_event;
 use crate::events::types::{HttpEvent, HttpEventType, ProcessedEvent};
-use crate::ui_components::{border_color, text_primary_color, text_secondary_color};
-use anyhow::Result;
+use crate::redaction::is_redacted;
+use crate::ui_components::{border_color, editor_link, text_primary_color, text_secondary_color};
 use gpui::prelude::*;
 use gpui::{div, rgb, Context, Div, FontWeight};
 use serde_json::Value;
 
-pub fn process(payload: &Value) -> Result<EventEntry> {
-    let mut entry = EventEntry {
-        timestamp: extract_timestamp(payload),
-        label: "request".to_string(),
-        description: String::new(),
-        content_type: "custom_ui".to_string(),
-        event_type: "request".to_string(),
-        raw_payload: payload.clone(),
-    };
-
-    if let Some(content) = payload.get("content") {
-        // Process using the new architecture
-        let processed_event = process_http_event(content)?;
-
-        // Set labels and descriptions based on processed event
-        if let ProcessedEvent::Http(ref http_event) = processed_event {
-            entry.label = match http_event.event_type {
-                HttpEventType::Request => "HTTP Request".to_string(),
-                HttpEventType::Response => "HTTP Response".to_string(),
-            };
-            let method_or_status = match http_event.event_type {
-                HttpEventType::Request => http_event.method.as_deref().unwrap_or("GET").to_string(),
-                HttpEventType::Response => http_event
-                    .status_code
-                    .map(|s| s.to_string())
-                    .unwrap_or_else(|| "Response".to_string()),
-            };
-            entry.description = format!("{} {}", method_or_status, http_event.url);
-        } else {
-            return Err(anyhow::anyhow!("Unexpected event type from http processor"));
-        }
-    }
-
-    Ok(entry)
-}
-
-pub fn render_http_event(entry: &EventEntry, _cx: &mut Context<crate::app::MyApp>) -> Div {
+pub fn render_http_event(entry: &EventEntry, cx: &mut Context<crate::app::MyApp>) -> Div {
     if let Some(content) = entry.raw_payload.get("content") {
         if let Ok(ProcessedEvent::Http(http_event)) = process_http_event(content) {
             div()
@@ -60,7 +24,7 @@ pub fn render_http_event(entry: &EventEntry, _cx: &mut Context<crate::app::MyApp
                         || http_event.size_bytes.is_some(),
                     |d| d.child(render_performance_metrics(&http_event)),
                 )
-                .child(render_origin_info(entry))
+                .child(render_origin_info(entry, cx))
         } else {
             div().child("Invalid HTTP event data")
         }
@@ -147,6 +111,7 @@ fn render_headers(http_event: &HttpEvent) -> Div {
                 .children({
                     let sorted_headers: BTreeMap<_, _> = http_event.headers.iter().collect();
                     sorted_headers.into_iter().map(|(key, value)| {
+                        let value = value.to_string();
                         div()
                             .flex()
                             .gap_2()
@@ -162,7 +127,11 @@ fn render_headers(http_event: &HttpEvent) -> Div {
                                 div()
                                     .flex_1()
                                     .text_color(text_primary_color())
-                                    .child(value.to_string()),
+                                    // Masked at ingestion, see `redaction`
+                                    .when(is_redacted(&value), |d| {
+                                        d.italic().text_color(rgb(0xf59e0b))
+                                    })
+                                    .child(value),
                             )
                     })
                 }),
@@ -183,10 +152,15 @@ fn render_body(http_event: &HttpEvent) -> Div {
             .gap_2()
             .child(
                 div()
+                    .flex()
+                    .gap_2()
                     .text_xs()
                     .font_weight(FontWeight::MEDIUM)
                     .text_color(text_secondary_color())
-                    .child("BODY"),
+                    .child("BODY")
+                    .when(is_redacted(&formatted_body), |d| {
+                        d.child(div().italic().text_color(rgb(0xf59e0b)).child("redacted"))
+                    }),
             )
             .child(
                 div()
@@ -283,7 +257,7 @@ fn render_performance_metrics(http_event: &HttpEvent) -> Div {
         )
 }
 
-fn render_origin_info(entry: &EventEntry) -> Div {
+fn render_origin_info(entry: &EventEntry, cx: &mut Context<crate::app::MyApp>) -> Div {
     if let Some(origin) = entry.raw_payload.get("origin") {
         let file = origin.get("file").and_then(Value::as_str).unwrap_or("");
         let line = origin
@@ -298,7 +272,7 @@ fn render_origin_info(entry: &EventEntry) -> Div {
             .text_xs()
             .text_color(text_secondary_color())
             .opacity(0.7)
-            .child(format!("{file}:{line}"))
+            .child(editor_link(format!("{file}:{line}"), file, line, cx))
     } else {
         div()
     }
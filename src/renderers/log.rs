@@ -1,57 +1,18 @@
-use crate::events::base::{extract_timestamp, EventEntry};
-use crate::events::processors::process_log_event;
-use crate::events::types::ProcessedEvent;
+use crate::events::base::EventEntry;
 use crate::ui_components::{
-    border_color, text_monospace_color, text_primary_color, text_secondary_color,
+    border_color, editor_link, text_monospace_color, text_primary_color, text_secondary_color,
 };
-use anyhow::Result;
 use gpui::prelude::*;
 use gpui::{div, Context, Div, InteractiveText, StyledText};
 use serde_json::Value;
 
-pub fn process(payload: &Value) -> Result<EventEntry> {
-    let mut entry = EventEntry {
-        timestamp: extract_timestamp(payload),
-        label: "log".to_string(),
-        description: String::new(),
-        content_type: "custom_ui".to_string(),
-        event_type: "log".to_string(),
-        raw_payload: payload.clone(),
-    };
-
-    if let Some(content) = payload.get("content") {
-        // Process using the new architecture
-        let processed_event = process_log_event(content)?;
-
-        // Set labels and descriptions based on processed event
-        if let ProcessedEvent::Log(ref log_event) = processed_event {
-            entry.label = "Log".to_string();
-            let description = if log_event.message.len() > 100 {
-                format!("{}...", &log_event.message[..97])
-            } else {
-                log_event.message.clone()
-            };
-            // Clean up any JSON formatting for the description
-            entry.description = description
-                .replace('\n', " ")
-                .replace("  ", " ")
-                .trim()
-                .to_string();
-        } else {
-            return Err(anyhow::anyhow!("Unexpected event type from log processor"));
-        }
-    }
-
-    Ok(entry)
-}
-
-pub fn render_log_event(entry: &EventEntry, _cx: &mut Context<crate::app::MyApp>) -> Div {
+pub fn render_log_event(entry: &EventEntry, cx: &mut Context<crate::app::MyApp>) -> Div {
     div()
         .flex()
         .flex_col()
         .gap_6()
         .child(render_log_values(entry))
-        .child(render_origin_info(entry))
+        .child(render_origin_info(entry, cx))
 }
 
 // Header removed for minimal design
@@ -158,7 +119,7 @@ fn render_complex_value(value: &Value) -> Div {
     )
 }
 
-fn render_origin_info(entry: &EventEntry) -> Div {
+fn render_origin_info(entry: &EventEntry, cx: &mut Context<crate::app::MyApp>) -> Div {
     if let Some(origin) = entry.raw_payload.get("origin") {
         let file = origin.get("file").and_then(|f| f.as_str()).unwrap_or("");
         let line = origin
@@ -180,7 +141,12 @@ fn render_origin_info(entry: &EventEntry) -> Div {
                         .text_xs()
                         .text_color(text_secondary_color())
                         .opacity(0.7)
-                        .child(format!("{file}:{line} • {hostname}")),
+                        .child(editor_link(
+                            format!("{file}:{line} • {hostname}"),
+                            file,
+                            line,
+                            cx,
+                        )),
                 )
         } else {
             div() // Empty div if no origin info
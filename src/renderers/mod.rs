@@ -0,0 +1,109 @@
+// renderers/mod.rs - GPUI detail views for each event type, on top of ray-core's processors
+pub mod application_log;
+pub mod cache;
+pub mod custom;
+pub mod exception;
+pub mod http;
+pub mod log;
+pub mod query;
+
+use crate::events::{registry, EventEntry};
+use gpui::Context;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// Function type for custom event UI renderers
+pub type EventUIRenderer = fn(&EventEntry, &mut Context<crate::app::MyApp>) -> gpui::Div;
+
+static RENDERERS: OnceLock<Mutex<HashMap<String, EventUIRenderer>>> = OnceLock::new();
+
+/// Renderers keyed by payload type name, seeded with the built-in ones
+fn renderers() -> &'static Mutex<HashMap<String, EventUIRenderer>> {
+    RENDERERS.get_or_init(|| {
+        let builtins: [(&str, EventUIRenderer); 7] = [
+            ("log", log::render_log_event),
+            ("exception", exception::render_exception_event),
+            ("query", query::render_query_event),
+            ("executed_query", query::render_query_event),
+            (
+                "application_log",
+                application_log::render_application_log_event,
+            ),
+            ("cache", cache::render_cache_event),
+            ("request", http::render_http_event),
+            // "table" removed - was an anti-pattern dispatcher
+        ];
+        Mutex::new(
+            builtins
+                .into_iter()
+                .map(|(name, renderer)| (name.to_string(), renderer))
+                .collect(),
+        )
+    })
+}
+
+/// Add or replace the renderer for a payload type name, e.g. for a type added
+/// with `EventRegistry::register_type`
+pub fn register_ui_renderer(event_type: &str, renderer: EventUIRenderer) {
+    renderers()
+        .lock()
+        .unwrap()
+        .insert(event_type.to_string(), renderer);
+}
+
+/// Get a custom UI renderer for the given event type
+pub fn get_ui_renderer(event_type: &str) -> Option<EventUIRenderer> {
+    if let Some(renderer) = renderers().lock().unwrap().get(event_type) {
+        return Some(*renderer);
+    }
+    if registry().definition(event_type).is_some() {
+        return Some(custom::render_custom_event);
+    }
+    Some(render_unknown_event) // Fallback for unknown event types
+}
+
+/// Fallback renderer for unknown event types
+fn render_unknown_event(
+    entry: &EventEntry,
+    _cx: &mut gpui::Context<crate::app::MyApp>,
+) -> gpui::Div {
+    use crate::ui_components::{border_color, text_primary_color, text_secondary_color};
+    use gpui::div;
+    use gpui::prelude::*;
+
+    div()
+        .flex()
+        .flex_col()
+        .gap_4()
+        .child(
+            div()
+                .text_sm()
+                .font_weight(gpui::FontWeight::MEDIUM)
+                .text_color(text_primary_color())
+                .child(format!("Unknown Event Type: {}", entry.event_type)),
+        )
+        .child(
+            div()
+                .text_xs()
+                .text_color(text_secondary_color())
+                .child("This event type is not supported. Raw JSON payload:"),
+        )
+        .child(
+            div()
+                .p_4()
+                .rounded_md()
+                .bg(gpui::rgb(0x18181b))
+                .border_1()
+                .border_color(border_color())
+                .child(
+                    div()
+                        .text_xs()
+                        .font_family("monospace")
+                        .text_color(text_primary_color())
+                        .child(
+                            serde_json::to_string_pretty(&entry.raw_payload)
+                                .unwrap_or_else(|_| "Invalid JSON".to_string()),
+                        ),
+                ),
+        )
+}
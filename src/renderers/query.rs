@@ -1,61 +1,12 @@
-use crate::events::base::{extract_timestamp, EventEntry};
-use crate::events::processors::process_query_event;
-use crate::events::types::ProcessedEvent;
+use crate::events::base::EventEntry;
 use crate::ui_components::{
-    border_color, text_monospace_color, text_primary_color, text_secondary_color,
+    border_color, editor_link, text_monospace_color, text_primary_color, text_secondary_color,
 };
-use anyhow::Result;
 use gpui::prelude::*;
 use gpui::{div, Context, Div, InteractiveText, StyledText};
 use serde_json::Value;
 
-pub fn process(payload: &Value) -> Result<EventEntry> {
-    let mut entry = EventEntry {
-        timestamp: extract_timestamp(payload),
-        label: "Query".to_string(),
-        description: String::new(),
-        content_type: "custom_ui".to_string(),
-        event_type: "query".to_string(),
-        raw_payload: payload.clone(),
-    };
-
-    if let Some(content) = payload.get("content") {
-        // Process using the new architecture
-        let processed_event = process_query_event(content)?;
-
-        // Set labels and descriptions based on processed event
-        if let ProcessedEvent::Query(ref query_event) = processed_event {
-            // Extract the SQL operation type (SELECT, INSERT, UPDATE, etc.)
-            let operation_type =
-                if let Some(first_word) = query_event.sql.split_whitespace().next() {
-                    first_word.to_uppercase()
-                } else {
-                    "SQL".to_string()
-                };
-            entry.label = format!("Query: {operation_type}");
-
-            let description_sql = if query_event.sql.len() > 50 {
-                format!("{}...", &query_event.sql[..50].trim())
-            } else {
-                query_event.sql.trim().to_string()
-            };
-
-            if let Some(time) = query_event.duration_ms {
-                entry.description = format!("{description_sql} ({time}ms)");
-            } else {
-                entry.description = description_sql;
-            }
-        } else {
-            return Err(anyhow::anyhow!(
-                "Unexpected event type from query processor"
-            ));
-        }
-    }
-
-    Ok(entry)
-}
-
-pub fn render_query_event(entry: &EventEntry, _cx: &mut Context<crate::app::MyApp>) -> Div {
+pub fn render_query_event(entry: &EventEntry, cx: &mut Context<crate::app::MyApp>) -> Div {
     let content = entry
         .raw_payload
         .get("content")
@@ -68,7 +19,7 @@ pub fn render_query_event(entry: &EventEntry, _cx: &mut Context<crate::app::MyAp
         .gap_6()
         .child(render_query_metrics(&content))
         .child(render_sql_query(&content))
-        .child(render_origin_info(entry))
+        .child(render_origin_info(entry, cx))
 }
 
 // Header removed for minimal design
@@ -129,7 +80,7 @@ fn render_sql_query(content: &Value) -> Div {
     )
 }
 
-fn render_origin_info(entry: &EventEntry) -> Div {
+fn render_origin_info(entry: &EventEntry, cx: &mut Context<crate::app::MyApp>) -> Div {
     if let Some(origin) = entry.raw_payload.get("origin") {
         let file = origin.get("file").and_then(|f| f.as_str()).unwrap_or("");
         let line = origin
@@ -151,7 +102,12 @@ fn render_origin_info(entry: &EventEntry) -> Div {
                         .text_xs()
                         .text_color(text_secondary_color())
                         .opacity(0.7)
-                        .child(format!("{file}:{line} • {hostname}")),
+                        .child(editor_link(
+                            format!("{file}:{line} • {hostname}"),
+                            file,
+                            line,
+                            cx,
+                        )),
                 )
         } else {
             div() // Empty div if no origin info
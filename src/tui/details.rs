@@ -0,0 +1,314 @@
+use crate::config::config;
+use crate::events::frames::{first_application_frame, FrameKind};
+use crate::events::processors::cache::process_cache_event;
+use crate::events::processors::http::process_http_event;
+use crate::events::processors::{
+    process_application_log_event, process_exception_event, process_log_event, process_query_event,
+};
+use crate::events::types::{
+    ApplicationLogEvent, CacheEvent, ExceptionEvent, HttpEvent, HttpEventType, LogEvent,
+    ProcessedEvent, QueryEvent,
+};
+use crate::events::{registry, EventEntry};
+use crate::redaction::is_redacted;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use serde_json::Value;
+
+const LABEL: Style = Style::new().fg(Color::DarkGray);
+const HEADING: Style = Style::new().fg(Color::Gray).add_modifier(Modifier::BOLD);
+const ERROR: Style = Style::new().fg(Color::Red);
+const WARNING: Style = Style::new().fg(Color::Yellow);
+const SUCCESS: Style = Style::new().fg(Color::Green);
+const REDACTED: Style = Style::new().fg(Color::Yellow).add_modifier(Modifier::ITALIC);
+
+/// Run the entry's payload through the same processors the GUI renderers use
+fn processed_event(entry: &EventEntry) -> Option<ProcessedEvent> {
+    let content = entry.raw_payload.get("content")?;
+    let result = match entry.event_type.as_str() {
+        "log" => process_log_event(content),
+        "exception" => process_exception_event(content),
+        "query" | "executed_query" => process_query_event(content),
+        "application_log" => process_application_log_event(content),
+        "cache" => process_cache_event(content),
+        "request" => process_http_event(content),
+        _ => return None,
+    };
+    result.ok()
+}
+
+/// Formatted details for the selected entry
+pub fn details_lines(entry: &EventEntry) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::styled(entry.label.clone(), HEADING),
+        Line::styled(entry.timestamp.clone(), LABEL),
+        Line::default(),
+    ];
+
+    match processed_event(entry) {
+        Some(ProcessedEvent::Log(log)) => log_lines(&log, &mut lines),
+        Some(ProcessedEvent::ApplicationLog(log)) => application_log_lines(&log, &mut lines),
+        Some(ProcessedEvent::Query(query)) => query_lines(&query, &mut lines),
+        Some(ProcessedEvent::Cache(cache)) => cache_lines(&cache, &mut lines),
+        Some(ProcessedEvent::Http(http)) => http_lines(&http, &mut lines),
+        Some(ProcessedEvent::Exception(exception)) => exception_lines(&exception, &mut lines),
+        None => match registry().definition(&entry.event_type) {
+            Some(definition) => {
+                text_lines(&entry.description, &mut lines);
+                heading("Fields", &mut lines);
+                for (label, value) in definition.field_values(&entry.raw_payload) {
+                    field(&label, value, &mut lines);
+                }
+            }
+            None => {
+                heading("Payload", &mut lines);
+                json_lines(&entry.raw_payload, &mut lines);
+            }
+        },
+    }
+
+    if let Some(origin) = entry.raw_payload.get("origin") {
+        if let Some(file) = origin.get("file").and_then(Value::as_str) {
+            let line = origin
+                .get("line_number")
+                .and_then(Value::as_u64)
+                .unwrap_or(0);
+            heading("Origin", &mut lines);
+            lines.push(Line::raw(format!("{file}:{line}")));
+        }
+    }
+
+    lines
+}
+
+fn heading(title: &str, lines: &mut Vec<Line<'static>>) {
+    if lines.last().is_some_and(|line| line.width() > 0) {
+        lines.push(Line::default());
+    }
+    lines.push(Line::styled(title.to_uppercase(), HEADING));
+}
+
+fn field(name: &str, value: impl Into<String>, lines: &mut Vec<Line<'static>>) {
+    let value = value.into();
+    let style = if is_redacted(&value) {
+        REDACTED
+    } else {
+        Style::new()
+    };
+    lines.push(Line::from(vec![
+        Span::styled(format!("{name:<16}"), LABEL),
+        Span::styled(value, style),
+    ]));
+}
+
+fn text_lines(text: &str, lines: &mut Vec<Line<'static>>) {
+    lines.extend(text.lines().map(|line| {
+        if is_redacted(line) {
+            Line::styled(line.to_string(), REDACTED)
+        } else {
+            Line::raw(line.to_string())
+        }
+    }));
+}
+
+fn json_lines(value: &Value, lines: &mut Vec<Line<'static>>) {
+    match value {
+        Value::String(s) => text_lines(s, lines),
+        other => text_lines(
+            &serde_json::to_string_pretty(other).unwrap_or_default(),
+            lines,
+        ),
+    }
+}
+
+fn level_style(level: &str) -> Style {
+    match level.to_lowercase().as_str() {
+        "error" | "critical" | "alert" | "emergency" => ERROR,
+        "warning" => WARNING,
+        _ => Style::new(),
+    }
+}
+
+fn log_lines(log: &LogEvent, lines: &mut Vec<Line<'static>>) {
+    lines.push(Line::styled(
+        log.level.to_uppercase(),
+        level_style(&log.level),
+    ));
+    text_lines(&log.message, lines);
+    if let Some(context) = &log.context {
+        heading("Context", lines);
+        json_lines(context, lines);
+    }
+}
+
+fn application_log_lines(log: &ApplicationLogEvent, lines: &mut Vec<Line<'static>>) {
+    let mut level = vec![Span::styled(
+        log.level.to_uppercase(),
+        level_style(&log.level),
+    )];
+    if let Some(channel) = &log.channel {
+        level.push(Span::styled(format!("  {channel}"), LABEL));
+    }
+    lines.push(Line::from(level));
+    text_lines(&log.message, lines);
+    if let Some(context) = &log.context {
+        heading("Context", lines);
+        json_lines(context, lines);
+    }
+}
+
+fn query_lines(query: &QueryEvent, lines: &mut Vec<Line<'static>>) {
+    heading("SQL", lines);
+    text_lines(&query.sql, lines);
+    heading("Details", lines);
+    if let Some(duration) = query.duration_ms {
+        field("Duration", format!("{duration} ms"), lines);
+    }
+    if let Some(connection) = &query.connection_name {
+        field("Connection", connection.clone(), lines);
+    }
+    if let Some(rows) = query.affected_rows {
+        field("Affected rows", rows.to_string(), lines);
+    }
+    if !query.bindings.is_empty() {
+        heading("Bindings", lines);
+        for (index, binding) in query.bindings.iter().enumerate() {
+            field(&index.to_string(), binding.to_string(), lines);
+        }
+    }
+}
+
+fn cache_lines(cache: &CacheEvent, lines: &mut Vec<Line<'static>>) {
+    let style = match cache.operation.as_str() {
+        "Hit" => SUCCESS,
+        "Missed" => ERROR,
+        _ => Style::new(),
+    };
+    lines.push(Line::styled(cache.operation.clone(), style));
+    field("Key", cache.key.clone(), lines);
+    if let Some(store) = &cache.store {
+        field("Store", store.clone(), lines);
+    }
+    if let Some(ttl) = &cache.ttl {
+        field("TTL", ttl.clone(), lines);
+    }
+    if let Some(seconds) = cache.expiration_seconds {
+        field("Expiration", format!("{seconds}s"), lines);
+    }
+    if let Some(tags) = &cache.tags {
+        field("Tags", tags.clone(), lines);
+    }
+    if let Some(value) = &cache.value {
+        heading("Value", lines);
+        json_lines(value, lines);
+    }
+}
+
+fn http_lines(http: &HttpEvent, lines: &mut Vec<Line<'static>>) {
+    let kind = match http.event_type {
+        HttpEventType::Request => "Request",
+        HttpEventType::Response => "Response",
+    };
+    let mut summary = vec![Span::styled(format!("{kind} "), HEADING)];
+    if let Some(method) = &http.method {
+        summary.push(Span::raw(format!("{method} ")));
+    }
+    if let Some(status) = http.status_code {
+        let style = match status {
+            500.. => ERROR,
+            400.. => WARNING,
+            _ => SUCCESS,
+        };
+        summary.push(Span::styled(format!("{status} "), style));
+    }
+    summary.push(Span::raw(http.url.clone()));
+    lines.push(Line::from(summary));
+
+    if let Some(duration) = http.duration_seconds {
+        field("Duration", format!("{duration}s"), lines);
+    }
+    if let Some(connection) = http.connection_time_seconds {
+        field("Connection time", format!("{connection}s"), lines);
+    }
+    if let Some(size) = http.size_bytes {
+        field("Size", format!("{size} bytes"), lines);
+    }
+    if let Some(content_type) = &http.content_type {
+        field("Content type", content_type.clone(), lines);
+    }
+
+    if !http.headers.is_empty() {
+        heading("Headers", lines);
+        let mut headers: Vec<_> = http.headers.iter().collect();
+        headers.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, value) in headers {
+            let value = value
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| value.to_string());
+            field(name, value, lines);
+        }
+    }
+    if let Some(body) = &http.body {
+        heading("Body", lines);
+        json_lines(body, lines);
+    }
+}
+
+fn exception_lines(exception: &ExceptionEvent, lines: &mut Vec<Line<'static>>) {
+    lines.push(Line::styled(exception.class.clone(), ERROR));
+    text_lines(&exception.message, lines);
+    lines.push(Line::styled(
+        format!("{}:{}", exception.file, exception.line),
+        LABEL,
+    ));
+
+    if !exception.stack_trace.is_empty() {
+        heading("Stack trace", lines);
+        let kinds: Vec<FrameKind> = exception
+            .stack_trace
+            .iter()
+            .map(|frame| config().frames.classify(frame))
+            .collect();
+        let culprit = first_application_frame(&kinds);
+
+        for (index, (frame, kind)) in exception.stack_trace.iter().zip(&kinds).enumerate() {
+            let style = match (Some(index) == culprit, kind) {
+                (true, _) => ERROR,
+                (false, FrameKind::Vendor) => LABEL,
+                (false, FrameKind::Application) => Style::new(),
+            };
+            let class = frame
+                .class
+                .as_deref()
+                .map(|class| format!("{class}::"))
+                .unwrap_or_default();
+            lines.push(Line::styled(
+                format!(
+                    "{index:>3} {class}{} {}:{}",
+                    frame.function, frame.file, frame.line
+                ),
+                style,
+            ));
+
+            if Some(index) == culprit {
+                for snippet in &frame.snippet {
+                    let style = if snippet.line_number == frame.line {
+                        ERROR
+                    } else {
+                        LABEL
+                    };
+                    lines.push(Line::styled(
+                        format!("      {:>5}  {}", snippet.line_number, snippet.text),
+                        style,
+                    ));
+                }
+            }
+        }
+    }
+
+    if let Some(context) = &exception.context {
+        heading("Context", lines);
+        json_lines(context, lines);
+    }
+}
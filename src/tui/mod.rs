@@ -0,0 +1,47 @@
+// tui/mod.rs - full-screen terminal front-end, an alternative to the GPUI window
+mod details;
+mod state;
+mod view;
+
+use crate::event_storage::EventStorage;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use state::TuiApp;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often the screen is redrawn to pick up newly received events
+const TICK_RATE: Duration = Duration::from_millis(250);
+
+pub fn run_tui(
+    payload_storage: Arc<EventStorage>,
+    shutdown_tx: tokio::sync::oneshot::Sender<()>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Console log lines would be drawn over the interface
+    payload_storage.set_console_output(false);
+
+    let mut terminal = ratatui::init();
+    let mut app = TuiApp::new(Arc::clone(&payload_storage));
+
+    let result = (|| -> std::io::Result<()> {
+        while !app.should_quit {
+            app.refresh();
+            terminal.draw(|frame| view::draw(frame, &app))?;
+
+            if event::poll(TICK_RATE)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        app.handle_key(key);
+                    }
+                }
+            }
+        }
+        Ok(())
+    })();
+
+    ratatui::restore();
+    payload_storage.set_console_output(true);
+    payload_storage.info("Tui", "Terminal UI closed, stopping server");
+    let _ = shutdown_tx.send(());
+
+    result.map_err(Into::into)
+}
@@ -0,0 +1,264 @@
+use crate::config::config;
+use crate::event_filter::EventFilter;
+use crate::event_storage::{EventStorage, LogRecord};
+use crate::events::{EventEntry, EventType};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Terminal counterpart of `MyApp`: filters, selection and search over the shared storage
+pub struct TuiApp {
+    storage: Arc<EventStorage>,
+    pub event_type_filters: HashSet<EventType>,
+    pub search: String,
+    /// Only show events from this project; all of them when `None`
+    pub project: Option<String>,
+    /// Projects of the configured listeners, in the order `p` cycles through them
+    projects: Vec<String>,
+    /// Whether keystrokes currently edit the search text
+    pub search_active: bool,
+    pub selected_row: usize,
+    pub details_scroll: u16,
+    /// Show the app's own diagnostics instead of the events
+    pub show_internal: bool,
+    pub should_quit: bool,
+    events: Vec<Arc<EventEntry>>,
+    /// Storage generation and filter the cached list was computed for
+    cached_for: Option<(u64, EventFilter)>,
+}
+
+impl TuiApp {
+    pub fn new(storage: Arc<EventStorage>) -> Self {
+        Self {
+            storage,
+            event_type_filters: EventType::all().into_iter().collect(),
+            search: String::new(),
+            project: None,
+            projects: config()
+                .server
+                .projects()
+                .map(|project| project.name.clone())
+                .collect(),
+            search_active: false,
+            selected_row: 0,
+            details_scroll: 0,
+            show_internal: false,
+            should_quit: false,
+            events: Vec::new(),
+            cached_for: None,
+        }
+    }
+
+    pub fn filter(&self) -> EventFilter {
+        let filter = EventFilter::new()
+            .with_types(self.event_type_filters.iter().copied())
+            .with_search(self.search.as_str());
+        match &self.project {
+            Some(project) => filter.with_projects([project.clone()]),
+            None => filter,
+        }
+    }
+
+    /// Recompute the visible events if storage or the filter changed since the last call
+    pub fn refresh(&mut self) {
+        let filter = self.filter();
+        let generation = self.storage.get_generation();
+        if self.cached_for.as_ref() == Some(&(generation, filter.clone())) {
+            return;
+        }
+
+        self.events = self
+            .storage
+            .get_events_optimized()
+            .into_iter()
+            .filter(|entry| filter.matches(entry))
+            .collect();
+        self.cached_for = Some((generation, filter));
+
+        if self.selected_row >= self.events.len() {
+            self.selected_row = self.events.len().saturating_sub(1);
+        }
+    }
+
+    pub fn events(&self) -> &[Arc<EventEntry>] {
+        &self.events
+    }
+
+    pub fn selected_entry(&self) -> Option<&Arc<EventEntry>> {
+        self.events.get(self.selected_row)
+    }
+
+    /// Server and app diagnostics, newest first
+    pub fn internal_log(&self) -> Vec<LogRecord> {
+        self.storage.internal_log()
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.should_quit = true;
+            return;
+        }
+
+        if self.search_active {
+            match key.code {
+                KeyCode::Enter | KeyCode::Esc => self.search_active = false,
+                KeyCode::Backspace => {
+                    self.search.pop();
+                }
+                KeyCode::Char(c) => self.search.push(c),
+                _ => {}
+            }
+            self.selected_row = 0;
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('q') => self.should_quit = true,
+            KeyCode::Down | KeyCode::Char('j') => self.select(self.selected_row.saturating_add(1)),
+            KeyCode::Up | KeyCode::Char('k') => self.select(self.selected_row.saturating_sub(1)),
+            KeyCode::Home | KeyCode::Char('g') => self.select(0),
+            KeyCode::End | KeyCode::Char('G') => self.select(self.events.len().saturating_sub(1)),
+            KeyCode::PageDown => self.details_scroll = self.details_scroll.saturating_add(10),
+            KeyCode::PageUp => self.details_scroll = self.details_scroll.saturating_sub(10),
+            KeyCode::Char('/') => self.search_active = true,
+            KeyCode::Char('i') => self.show_internal = !self.show_internal,
+            KeyCode::Char('p') => self.cycle_project(),
+            KeyCode::Esc => {
+                self.search.clear();
+                self.selected_row = 0;
+            }
+            KeyCode::Char('c') => {
+                self.storage.clear_events();
+                self.selected_row = 0;
+            }
+            KeyCode::Char('a') => {
+                // Toggle between all types and none
+                if self.event_type_filters.len() == EventType::all().len() {
+                    self.event_type_filters.clear();
+                } else {
+                    self.event_type_filters = EventType::all().into_iter().collect();
+                }
+                self.selected_row = 0;
+            }
+            KeyCode::Char(c @ '1'..='9') => {
+                let index = c as usize - '1' as usize;
+                if let Some(event_type) = EventType::all().get(index) {
+                    self.toggle_event_type_filter(*event_type);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn select(&mut self, index: usize) {
+        if index < self.events.len() && index != self.selected_row {
+            self.selected_row = index;
+            self.details_scroll = 0;
+        }
+    }
+
+    /// All projects, then each one on its own, then all again
+    fn cycle_project(&mut self) {
+        let next = match &self.project {
+            Some(current) => self
+                .projects
+                .iter()
+                .position(|p| p == current)
+                .map(|i| i + 1),
+            None => Some(0),
+        };
+        self.project = next.and_then(|index| self.projects.get(index)).cloned();
+        self.selected_row = 0;
+    }
+
+    fn toggle_event_type_filter(&mut self, event_type: EventType) {
+        if !self.event_type_filters.remove(&event_type) {
+            self.event_type_filters.insert(event_type);
+        }
+        self.selected_row = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_storage::LogLevel;
+    use serde_json::json;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_type_toggle_and_search() {
+        let storage = Arc::new(EventStorage::new());
+        storage.set_console_output(false);
+        storage.add_event(&json!({"type": "log", "content": {"values": ["payment failed"]}}));
+        storage.add_event(&json!({"type": "log", "content": {"values": ["user created"]}}));
+        storage.add_event(&json!({"type": "query", "content": {"sql": "select 1"}}));
+
+        let mut app = TuiApp::new(storage);
+        app.refresh();
+        assert_eq!(app.events().len(), 3);
+
+        // "4" toggles the fourth type, Query
+        app.handle_key(key(KeyCode::Char('4')));
+        app.refresh();
+        assert_eq!(app.events().len(), 2);
+
+        for code in [
+            KeyCode::Char('/'),
+            KeyCode::Char('p'),
+            KeyCode::Char('a'),
+            KeyCode::Enter,
+        ] {
+            app.handle_key(key(code));
+        }
+        app.refresh();
+        assert_eq!(app.search, "pa");
+        assert_eq!(app.events().len(), 1);
+    }
+
+    #[test]
+    fn test_project_cycle() {
+        let mut app = TuiApp::new(Arc::new(EventStorage::new()));
+        app.projects = vec!["shop".to_string(), "api".to_string()];
+
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            app.handle_key(key(KeyCode::Char('p')));
+            seen.push(app.project.clone());
+        }
+        assert_eq!(
+            seen,
+            [
+                Some("shop".to_string()),
+                Some("api".to_string()),
+                None,
+                Some("shop".to_string())
+            ]
+        );
+        let projects = app.filter().projects.unwrap();
+        assert!(projects.len() == 1 && projects.contains("shop"));
+    }
+
+    #[test]
+    fn test_internal_log_view() {
+        let storage = Arc::new(EventStorage::new());
+        storage.set_console_output(false);
+        storage.debug("Request", "per-payload detail");
+        storage.error("Server", "Accept error: too many open files");
+
+        let mut app = TuiApp::new(storage);
+        app.handle_key(key(KeyCode::Char('i')));
+        assert!(app.show_internal);
+
+        // Debug messages only go to the console
+        let log = app.internal_log();
+        assert_eq!(log[0].message, "Accept error: too many open files");
+        assert!(log.iter().all(|record| record.level > LogLevel::Debug));
+
+        app.handle_key(key(KeyCode::Char('i')));
+        assert!(!app.show_internal);
+    }
+}
@@ -0,0 +1,161 @@
+use super::details::details_lines;
+use super::state::TuiApp;
+use crate::config::config;
+use crate::event_storage::LogLevel;
+use crate::events::EventType;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::Frame;
+
+const MUTED: Style = Style::new().fg(Color::DarkGray);
+
+pub fn draw(frame: &mut Frame, app: &TuiApp) {
+    let [main, footer] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+    if app.show_internal {
+        draw_internal_log(frame, app, main);
+    } else {
+        let [list_area, details_area] =
+            Layout::horizontal([Constraint::Length(48), Constraint::Min(1)]).areas(main);
+        draw_event_list(frame, app, list_area);
+        draw_details(frame, app, details_area);
+    }
+    frame.render_widget(Paragraph::new(footer_line(app)), footer);
+}
+
+fn draw_event_list(frame: &mut Frame, app: &TuiApp, area: ratatui::layout::Rect) {
+    let [filters_area, list_area] =
+        Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).areas(area);
+
+    // One toggle per type, numbered for the 1-9 shortcuts, three per row
+    let filters: Vec<Span> = EventType::all()
+        .into_iter()
+        .enumerate()
+        .map(|(index, event_type)| {
+            let style = if app.event_type_filters.contains(&event_type) {
+                Style::new().add_modifier(Modifier::BOLD)
+            } else {
+                MUTED
+            };
+            Span::styled(
+                format!("{} {}  ", index + 1, event_type.display_name()),
+                style,
+            )
+        })
+        .collect();
+    let filter_rows: Vec<Line> = filters
+        .chunks(3)
+        .map(|row| Line::from(row.to_vec()))
+        .collect();
+    frame.render_widget(
+        Paragraph::new(filter_rows).block(Block::default().borders(Borders::BOTTOM)),
+        filters_area,
+    );
+
+    let items: Vec<ListItem> = app
+        .events()
+        .iter()
+        .map(|entry| {
+            let mut header = vec![
+                Span::styled(
+                    entry.label.clone(),
+                    Style::new().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(format!("  {}", entry.timestamp), MUTED),
+            ];
+            // Tagged rows get a bar and the project name in the project's color
+            let project = entry
+                .project
+                .as_deref()
+                .and_then(|name| config().server.project(name));
+            let mut description = vec![Span::styled(entry.description.clone(), MUTED)];
+            if let Some(project) = project {
+                let color = Style::new().fg(Color::from_u32(project.color));
+                header.insert(0, Span::styled("▌", color));
+                header.push(Span::styled(format!("  {}", project.name), color));
+                description.insert(0, Span::styled("▌", color));
+            }
+            ListItem::new(vec![Line::from(header), Line::from(description)])
+        })
+        .collect();
+
+    let title = format!(" Events ({}) ", app.events().len());
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::RIGHT).title(title))
+        .highlight_style(Style::new().bg(Color::Rgb(0x27, 0x27, 0x2a)));
+
+    let mut state =
+        ListState::default().with_selected(app.selected_entry().map(|_| app.selected_row));
+    frame.render_stateful_widget(list, list_area, &mut state);
+}
+
+fn draw_details(frame: &mut Frame, app: &TuiApp, area: ratatui::layout::Rect) {
+    let lines = match app.selected_entry() {
+        Some(entry) => details_lines(entry),
+        None => vec![Line::styled("Select an event to view details", MUTED)],
+    };
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .scroll((app.details_scroll, 0))
+            .block(Block::default().title(" Details ")),
+        area,
+    );
+}
+
+fn draw_internal_log(frame: &mut Frame, app: &TuiApp, area: ratatui::layout::Rect) {
+    let records = app.internal_log();
+    let lines: Vec<Line> = records
+        .iter()
+        .map(|record| {
+            let level_style = match record.level {
+                LogLevel::Error => Style::new().fg(Color::Red),
+                LogLevel::Warn => Style::new().fg(Color::Yellow),
+                LogLevel::Info | LogLevel::Debug => MUTED,
+            };
+            Line::from(vec![
+                Span::styled(format!("{} ", record.timestamp), MUTED),
+                Span::styled(format!("{:<5} ", record.level.as_str()), level_style),
+                Span::styled(
+                    format!("{} ", record.source),
+                    Style::new().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(record.message.clone()),
+            ])
+        })
+        .collect();
+
+    let title = format!(" Internal ({}) ", records.len());
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().title(title)),
+        area,
+    );
+}
+
+fn footer_line(app: &TuiApp) -> Line<'static> {
+    if app.search_active {
+        return Line::from(vec![
+            Span::styled("/", Style::new().add_modifier(Modifier::BOLD)),
+            Span::raw(app.search.clone()),
+            Span::styled("▏  enter/esc to finish", MUTED),
+        ]);
+    }
+
+    let mut spans = Vec::new();
+    if !app.search.is_empty() {
+        spans.push(Span::raw(format!("search: {}  ", app.search)));
+    }
+    if let Some(project) = &app.project {
+        spans.push(Span::raw(format!("project: {project}  ")));
+    }
+    spans.push(Span::styled(
+        "↑↓/jk select  1-6 types  a all  / search  esc reset  c clear  p project  i internal  pgup/pgdn scroll  q quit",
+        MUTED,
+    ));
+    Line::from(spans)
+}
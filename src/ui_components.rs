@@ -33,3 +33,100 @@ pub fn copy_button() -> Div {
         .hover(|style| style.text_color(text_primary_color()))
         .child("copy raw payload")
 }
+
+/// Clickable `file:line` text that opens the location in the configured editor
+pub fn editor_link(
+    text: String,
+    file: &str,
+    line: u64,
+    cx: &mut gpui::Context<crate::app::MyApp>,
+) -> Div {
+    let file = file.to_string();
+    div()
+        .cursor_pointer()
+        .hover(|style| style.text_color(text_primary_color()))
+        .on_mouse_down(
+            gpui::MouseButton::Left,
+            cx.listener(move |this, _event, _window, cx| {
+                this.open_in_editor(&file, line, cx);
+            }),
+        )
+        .child(text)
+}
+
+/// Notification toasts stacked in the bottom-right corner of the window
+pub fn render_toasts(
+    toasts: &[crate::notifications::sinks::Toast],
+    cx: &mut gpui::Context<crate::app::MyApp>,
+) -> Div {
+    div()
+        .absolute()
+        .bottom_4()
+        .right_4()
+        .w(gpui::px(320.0))
+        .flex()
+        .flex_col()
+        .gap_2()
+        .children(toasts.iter().map(|toast| render_toast(toast, cx)))
+}
+
+fn render_toast(
+    toast: &crate::notifications::sinks::Toast,
+    cx: &mut gpui::Context<crate::app::MyApp>,
+) -> Div {
+    let id = toast.id;
+    let event_id = toast.notification.event_id;
+
+    div()
+        .p_3()
+        .rounded_md()
+        .bg(selection_color())
+        .border_1()
+        .border_color(border_color())
+        .flex()
+        .gap_2()
+        .child(
+            div()
+                .flex_1()
+                .flex()
+                .flex_col()
+                .gap_1()
+                .cursor_pointer()
+                .on_mouse_down(
+                    gpui::MouseButton::Left,
+                    cx.listener(move |this, _event, _window, cx| {
+                        if let Some(event_id) = event_id {
+                            this.select_event_by_id(event_id, cx);
+                        }
+                        this.dismiss_toast(id, cx);
+                    }),
+                )
+                .child(
+                    div()
+                        .text_sm()
+                        .font_weight(gpui::FontWeight::MEDIUM)
+                        .text_color(text_primary_color())
+                        .child(toast.notification.title.clone()),
+                )
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(text_secondary_color())
+                        .child(toast.notification.body.clone()),
+                ),
+        )
+        .child(
+            div()
+                .text_xs()
+                .text_color(text_secondary_color())
+                .cursor_pointer()
+                .hover(|style| style.text_color(text_primary_color()))
+                .on_mouse_down(
+                    gpui::MouseButton::Left,
+                    cx.listener(move |this, _event, _window, cx| {
+                        this.dismiss_toast(id, cx);
+                    }),
+                )
+                .child("×"),
+        )
+}